@@ -0,0 +1,61 @@
+use std::path::{Path, PathBuf};
+
+use space_thumbnails::{SpaceThumbnailsError, SpaceThumbnailsRenderer};
+
+/// A model given on the command line: a file, a ZIP archive whose primary model is loaded, or an
+/// entry of a ZIP archive written as `archive.zip!/path/model.gltf`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    File(PathBuf),
+    Archive {
+        archive: PathBuf,
+        entry: Option<String>,
+    },
+}
+
+impl Input {
+    pub fn parse(path: &Path) -> Self {
+        let value = path.to_string_lossy();
+        if let Some(index) = value.to_ascii_lowercase().find(".zip!") {
+            let (archive, entry) = value.split_at(index + ".zip".len());
+            return Self::Archive {
+                archive: PathBuf::from(archive),
+                entry: Some(entry[1..].trim_start_matches(['/', '\\']).to_owned())
+                    .filter(|entry| !entry.is_empty()),
+            };
+        }
+        match path.extension() {
+            Some(extension) if extension.eq_ignore_ascii_case("zip") => Self::Archive {
+                archive: path.to_owned(),
+                entry: None,
+            },
+            _ => Self::File(path.to_owned()),
+        }
+    }
+
+    pub fn load(&self, renderer: &mut SpaceThumbnailsRenderer) -> Result<(), SpaceThumbnailsError> {
+        match self {
+            Self::File(path) => renderer.load_asset_from_file(path)?,
+            Self::Archive { archive, entry } => {
+                renderer.load_asset_from_archive(archive, entry.as_deref())?
+            }
+        };
+        Ok(())
+    }
+
+    /// Path the names of the generated files are derived from, an archive entry is named after
+    /// its file but placed next to the archive.
+    pub fn output_base(&self) -> PathBuf {
+        match self {
+            Self::File(path)
+            | Self::Archive {
+                archive: path,
+                entry: None,
+            } => path.clone(),
+            Self::Archive {
+                archive,
+                entry: Some(entry),
+            } => archive.with_file_name(Path::new(entry).file_name().unwrap_or_default()),
+        }
+    }
+}
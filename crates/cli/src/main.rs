@@ -1,64 +1,386 @@
-use std::path::PathBuf;
+mod input;
+mod options;
 
-use clap::{ArgEnum, Parser};
-use image::{ImageBuffer, Rgba};
-use space_thumbnails::{SpaceThumbnailsRenderer, RendererBackend};
+use std::{
+    error::Error,
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+    process,
+    time::Instant,
+};
+
+use clap::{ArgGroup, Args, Parser, Subcommand};
+use image::{DynamicImage, RgbaImage};
+use input::Input;
+use options::{BackendApi, CameraView, OptionArgs};
+use space_thumbnails::{
+    encode_animation, AnimationFormat, CameraPreset, ContactSheetOptions, ModelInfo,
+    PlaybackOptions, RenderOptions, SizeStrategy, SpaceThumbnailsRenderer, TurntableOptions,
+};
 
 /// A command line tool for generating thumbnails for 3D model files.
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
-struct Args {
+struct Cli {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Render a thumbnail of a model file
+    Render(RenderArgs),
+    /// Print the statistics of a model file
+    Info(InfoArgs),
+    /// Render thumbnails of several model files with the same options
+    Batch(BatchArgs),
+}
+
+#[derive(Args, Debug)]
+#[clap(group(ArgGroup::new("mode").args(&["contact-sheet", "turntable", "playback", "sizes"])))]
+struct RenderArgs {
     /// The output file
     output: PathBuf,
 
-    // The 3D model file for which you want to generate thumbnail.
+    // The 3D model file for which you want to generate thumbnail, `archive.zip` or
+    // `archive.zip!/path/model.gltf` to read it from a ZIP archive.
     #[clap(short, long)]
     input: PathBuf,
 
+    #[clap(flatten)]
+    options: OptionArgs,
+
+    /// Render a grid of several viewpoints instead of a single view
+    #[clap(long)]
+    contact_sheet: bool,
+
+    /// Viewpoints of the contact sheet, defaults to front, right, top and isometric
+    #[clap(long, arg_enum, use_value_delimiter = true)]
+    views: Vec<CameraView>,
+
+    /// Number of columns of the contact sheet
+    #[clap(long)]
+    columns: Option<u32>,
+
+    /// Size of each contact sheet cell, defaults to the thumbnail size
+    #[clap(long)]
+    cell_size: Option<u32>,
+
+    /// Space between contact sheet cells in pixels
+    #[clap(long, default_value_t = 8)]
+    gutter: u32,
+
+    /// Do not label the contact sheet cells
+    #[clap(long)]
+    no_labels: bool,
+
+    /// Render an animation of the camera orbiting the model, the format (png, gif or webp) is
+    /// picked from the output file extension
+    #[clap(long)]
+    turntable: bool,
+
+    /// Play the animation selected with --animation (the first one by default) from a fixed
+    /// camera, the format is picked from the output file extension like for --turntable
+    #[clap(long)]
+    playback: bool,
+
+    /// Sampled frames per second of the animation playback
+    #[clap(long, default_value_t = 15.0)]
+    frame_rate: f32,
+
+    /// Number of turntable frames
+    #[clap(long, default_value_t = 36)]
+    frames: u32,
+
+    /// Rotation between two turntable frames in degrees
+    #[clap(long, default_value_t = 10.0)]
+    degrees_per_frame: f32,
+
+    /// Camera elevation of the turntable in degrees
+    #[clap(long, default_value_t = 20.0)]
+    elevation: f32,
+
+    /// How long each turntable frame is shown, in milliseconds
+    #[clap(long, default_value_t = 50)]
+    frame_delay: u32,
+
+    /// Save one image per size, e.g. `64,256,1024`, named like `output-64.png`. Each size is the
+    /// longest edge of the image
+    #[clap(long, use_value_delimiter = true)]
+    sizes: Vec<u32>,
+
+    /// Render every size natively instead of downsampling the largest one
+    #[clap(long)]
+    native_sizes: bool,
+}
+
+#[derive(Args, Debug)]
+struct InfoArgs {
+    /// The 3D model file to inspect, `archive.zip` or `archive.zip!/path/model.gltf` to read it
+    /// from a ZIP archive
+    input: PathBuf,
+
+    /// Print the statistics as JSON
+    #[clap(long)]
+    json: bool,
+
     // Specify the backend API
-    #[clap(short, long, arg_enum, default_value_t)]
-    api: BackendApi,
+    #[clap(short, long, arg_enum)]
+    api: Option<BackendApi>,
+}
 
-    // Generated thumbnail width
-    #[clap(short, long, default_value_t = 800)]
-    width: u32,
+#[derive(Args, Debug)]
+struct BatchArgs {
+    /// The 3D model files, the thumbnails are named after them. ZIP archives are accepted like for
+    /// the render command
+    #[clap(required = true)]
+    inputs: Vec<PathBuf>,
 
-    // Generated thumbnail height
-    #[clap(short, long, default_value_t = 800)]
-    height: u32,
+    /// Directory of the generated thumbnails, defaults to the directory of each model
+    #[clap(short = 'd', long)]
+    output_dir: Option<PathBuf>,
+
+    /// Image format of the generated thumbnails
+    #[clap(long, default_value = "png")]
+    format: String,
+
+    #[clap(flatten)]
+    options: OptionArgs,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ArgEnum)]
-enum BackendApi {
-    Default,
-    OpenGL,
-    Vulkan,
-    Metal,
+fn main() {
+    let cli = Cli::parse();
+
+    let result = match &cli.command {
+        Command::Render(args) => render(args),
+        Command::Info(args) => info(args),
+        Command::Batch(args) => batch(args),
+    };
+
+    if let Err(err) = result {
+        eprintln!("Error: {}", err);
+        let mut source = err.source();
+        while let Some(cause) = source {
+            eprintln!("  Caused by: {}", cause);
+            source = cause.source();
+        }
+        process::exit(1);
+    }
 }
 
-impl Default for BackendApi {
-    fn default() -> Self {
-        Self::Default
+fn render(args: &RenderArgs) -> Result<(), Box<dyn Error>> {
+    let mut renderer = SpaceThumbnailsRenderer::with_options(&args.options.render_options()?)?;
+    Input::parse(&args.input).load(&mut renderer)?;
+
+    if args.turntable || args.playback {
+        let format = args
+            .output
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(AnimationFormat::from_extension)
+            .ok_or("unsupported animation format, use png, gif or webp")?;
+        let frames = if args.playback {
+            renderer.render_animation(&PlaybackOptions {
+                animation: renderer
+                    .get_animation_pose()
+                    .map(|pose| pose.animation.clone())
+                    .unwrap_or_default(),
+                frame_rate: args.frame_rate,
+                ..PlaybackOptions::default()
+            })?
+        } else {
+            renderer.render_turntable(&TurntableOptions {
+                frames: args.frames,
+                degrees_per_frame: args.degrees_per_frame,
+                elevation: args.elevation,
+                frame_delay_ms: args.frame_delay,
+                ..TurntableOptions::default()
+            })?
+        };
+        encode_animation(&frames, format, BufWriter::new(File::create(&args.output)?))?;
+        return Ok(());
     }
+
+    if !args.sizes.is_empty() {
+        let strategy = if args.native_sizes {
+            SizeStrategy::Native
+        } else {
+            SizeStrategy::Downsample
+        };
+        let images = renderer.render_sizes(&args.sizes, strategy)?;
+        for (size, image) in args.sizes.iter().zip(images) {
+            save_image(image, &sized_output_path(&args.output, *size))?;
+        }
+        return Ok(());
+    }
+
+    let image = if args.contact_sheet {
+        let mut options = ContactSheetOptions {
+            columns: args.columns,
+            cell_size: args.cell_size.map(|size| (size, size)),
+            gutter: args.gutter,
+            labels: !args.no_labels,
+            ..ContactSheetOptions::default()
+        };
+        if !args.views.is_empty() {
+            options.views = args
+                .views
+                .iter()
+                .map(|view| CameraPreset::from(*view).into())
+                .collect();
+        }
+        renderer.render_contact_sheet(&options)?
+    } else {
+        renderer.take_screenshot_image()?
+    };
+    save_image(image, &args.output)?;
+
+    Ok(())
 }
 
-fn main() {
-    let args = Args::parse();
-
-    let mut renderer = SpaceThumbnailsRenderer::new(
-        match args.api {
-            BackendApi::Default => RendererBackend::Default,
-            BackendApi::OpenGL => RendererBackend::OpenGL,
-            BackendApi::Vulkan => RendererBackend::Vulkan,
-            BackendApi::Metal => RendererBackend::Metal,
-        },
-        args.width,
-        args.height,
-    );
-    renderer.load_asset_from_file(&args.input).unwrap();
-    let mut screenshot_buffer = vec![0; renderer.get_screenshot_size_in_byte()];
-    renderer.take_screenshot_sync(screenshot_buffer.as_mut_slice());
-
-    let image = ImageBuffer::<Rgba<u8>, _>::from_raw(args.width, args.height, screenshot_buffer).unwrap();
-    image.save(args.output).unwrap();
+fn info(args: &InfoArgs) -> Result<(), Box<dyn Error>> {
+    let mut options = RenderOptions::builder().size(64, 64);
+    if let Some(api) = args.api {
+        options = options.backend(api.into());
+    }
+    let mut renderer = SpaceThumbnailsRenderer::with_options(&options.build())?;
+
+    let now = Instant::now();
+    Input::parse(&args.input).load(&mut renderer)?;
+    let load_time = now.elapsed();
+    let info = renderer
+        .get_model_info()
+        .ok_or("the model did not report any information")?;
+
+    if args.json {
+        let mut value = serde_json::to_value(info)?;
+        value["load_time_ms"] = (load_time.as_secs_f64() * 1000.0).into();
+        println!("{}", serde_json::to_string_pretty(&value)?);
+    } else {
+        print_info(info);
+        println!("load time: {:.2?}", load_time);
+    }
+    Ok(())
+}
+
+fn print_info(info: &ModelInfo) {
+    let count = |count: Option<u64>| match count {
+        Some(count) => count.to_string(),
+        None => "unknown".to_owned(),
+    };
+    let names = |names: &Option<Vec<String>>| match names {
+        Some(names) if names.is_empty() => "none".to_owned(),
+        Some(names) => names.join(", "),
+        None => "unknown".to_owned(),
+    };
+
+    match info.detected_format {
+        Some(_) => println!("format: {}", info.format),
+        None => println!("format: {} (from the file extension)", info.format),
+    }
+    println!("bounds: {:?} - {:?}", info.bounds.min, info.bounds.max);
+    println!("triangles: {}", count(info.triangle_count));
+    println!("vertices: {}", count(info.vertex_count));
+    println!("meshes: {}", count(info.mesh_count.map(u64::from)));
+    println!("materials: {}", count(info.material_count.map(u64::from)));
+    println!("textures: {}", count(info.texture_count.map(u64::from)));
+    println!("nodes: {}", count(info.node_count.map(u64::from)));
+    println!("cameras: {}", names(&info.cameras));
+    println!("lights: {}", names(&info.lights));
+    println!("animations: {}", names(&info.animations));
+    println!("scenes: {}", names(&info.scenes));
+    println!("material variants: {}", names(&info.material_variants));
+    if !info.extensions_used.is_empty() {
+        println!("extensions: {}", info.extensions_used.join(", "));
+    }
+}
+
+fn batch(args: &BatchArgs) -> Result<(), Box<dyn Error>> {
+    let mut renderer = SpaceThumbnailsRenderer::with_options(&args.options.render_options()?)?;
+    if let Some(output_dir) = &args.output_dir {
+        fs::create_dir_all(output_dir)?;
+    }
+
+    let mut failed = 0;
+    for input in &args.inputs {
+        let model = Input::parse(input);
+        let output_base = model.output_base();
+        let output = match &args.output_dir {
+            Some(output_dir) => output_dir.join(output_base.file_name().unwrap_or_default()),
+            None => output_base,
+        }
+        .with_extension(&args.format);
+
+        let result = model
+            .load(&mut renderer)
+            .map_err(Box::<dyn Error>::from)
+            .and_then(|()| Ok(renderer.take_screenshot_image()?))
+            .and_then(|image| save_image(image, &output));
+        match result {
+            Ok(()) => println!("{} -> {}", input.display(), output.display()),
+            Err(err) => {
+                failed += 1;
+                eprintln!("{}: {}", input.display(), err);
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("{} of {} models failed", failed, args.inputs.len()).into());
+    }
+    Ok(())
+}
+
+/// `thumbnail.png` becomes `thumbnail-64.png`.
+fn sized_output_path(output: &Path, size: u32) -> PathBuf {
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    let filename = match output.extension() {
+        Some(extension) => format!("{}-{}.{}", stem, size, extension.to_string_lossy()),
+        None => format!("{}-{}", stem, size),
+    };
+    output.with_file_name(filename)
+}
+
+fn save_image(image: RgbaImage, path: &Path) -> Result<(), Box<dyn Error>> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    if matches!(extension.as_deref(), Some("jpg" | "jpeg")) {
+        // jpeg has no alpha channel
+        DynamicImage::ImageRgba8(image).to_rgb8().save(path)?;
+    } else {
+        image.save(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use clap::CommandFactory;
+
+    use super::Cli;
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn exclusive_render_modes() {
+        let render = |args: &[&str]| {
+            Cli::command().try_get_matches_from(
+                ["space-thumbnails", "render", "-i", "model.glb", "out.png"]
+                    .iter()
+                    .chain(args),
+            )
+        };
+        assert!(render(&["--turntable"]).is_ok());
+        assert!(render(&["--sizes", "64,256"]).is_ok());
+        assert!(render(&["--turntable", "--playback"]).is_err());
+        assert!(render(&["--playback", "--sizes", "64"]).is_err());
+        assert!(render(&["--contact-sheet", "--turntable"]).is_err());
+        assert!(render(&["--contact-sheet", "--sizes", "64"]).is_err());
+    }
 }
@@ -0,0 +1,481 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::{ArgEnum, Args};
+use space_thumbnails::{
+    AnimationPose, AnimationSelector, AnimationTime, AutoCrop, Background, CameraPreset,
+    EmbeddedCamera, Environment, EnvironmentPreset, EnvironmentSource, Exposure, LightingPreset,
+    LightingSpec, ProjectionMode, RenderOptions, RendererBackend, SceneSelector, ToneMapping,
+};
+
+/// Flags controlling the look of the rendered thumbnails.
+#[derive(Args, Debug)]
+pub struct OptionArgs {
+    /// Render options file in TOML or JSON, the other flags override its values
+    #[clap(long)]
+    pub config: Option<PathBuf>,
+
+    // Specify the backend API
+    #[clap(short, long, arg_enum)]
+    pub api: Option<BackendApi>,
+
+    // Generated thumbnail width, defaults to 800
+    #[clap(short, long)]
+    pub width: Option<u32>,
+
+    // Generated thumbnail height, defaults to 800
+    #[clap(short = 'H', long)]
+    pub height: Option<u32>,
+
+    /// Camera viewpoint, defaults to the camera stored in the file or a three-quarter view
+    #[clap(short, long, arg_enum)]
+    pub camera: Option<CameraView>,
+
+    /// Camera stored in the file to use, given by index or name, defaults to the first one
+    #[clap(
+        long,
+        parse(from_str = parse_embedded_camera),
+        conflicts_with = "ignore-embedded-cameras"
+    )]
+    pub embedded_camera: Option<EmbeddedCamera>,
+
+    /// Do not use the cameras stored in the file
+    #[clap(long)]
+    pub ignore_embedded_cameras: bool,
+
+    /// Background: `transparent`, a color like `#202020`, a vertical gradient like
+    /// `#ffffff,#c0c0c0` or the path of a backdrop image
+    #[clap(short, long, parse(try_from_str = parse_background))]
+    pub background: Option<Background>,
+
+    /// Lighting environment: `studio`, `outdoor`, `neutral-grey` or the path of a KTX cubemap
+    #[clap(short, long, parse(from_str = parse_environment))]
+    pub environment: Option<EnvironmentSource>,
+
+    /// Intensity of the lighting environment in lux, defaults to the intensity of the preset
+    #[clap(long)]
+    pub environment_intensity: Option<f32>,
+
+    /// Rotation of the lighting environment around the vertical axis in degrees
+    #[clap(long)]
+    pub environment_rotation: Option<f32>,
+
+    /// Show the lighting environment behind the model
+    #[clap(long)]
+    pub skybox: bool,
+
+    /// Direct lights of the scene
+    #[clap(short, long, arg_enum)]
+    pub lighting: Option<LightingRig>,
+
+    /// Disable shadows
+    #[clap(long)]
+    pub no_shadows: bool,
+
+    /// Exposure: an EV100 value like `15`, `aperture,shutter,iso` like `16,1/125,100`, or `auto`
+    /// with an optional compensation in stops like `auto:+1`
+    #[clap(short = 'x', long, parse(try_from_str = parse_exposure))]
+    pub exposure: Option<Exposure>,
+
+    /// Tone mapping operator
+    #[clap(short, long, arg_enum)]
+    pub tone_mapping: Option<ToneMapper>,
+
+    /// Space kept around the model in percent of the thumbnail size, defaults to 5
+    #[clap(short, long)]
+    pub margin: Option<f32>,
+
+    /// Samples per pixel of multisample anti-aliasing, 1 disables it
+    #[clap(long)]
+    pub msaa: Option<u8>,
+
+    /// Disable the FXAA post-process
+    #[clap(long)]
+    pub no_fxaa: bool,
+
+    /// Render at this multiple of the output size and filter the result down
+    #[clap(long)]
+    pub supersampling: Option<u32>,
+
+    /// Crop the thumbnail to the model and center it again
+    #[clap(long)]
+    pub auto_crop: bool,
+
+    /// Padding around the auto-cropped model in percent of the thumbnail size, implies
+    /// --auto-crop
+    #[clap(long)]
+    pub padding: Option<f32>,
+
+    /// Use an orthographic projection fitted to the model
+    #[clap(short, long)]
+    pub orthographic: bool,
+
+    /// glTF scene to render, given by index or name, defaults to the default scene
+    #[clap(long, parse(from_str = parse_scene))]
+    pub scene: Option<SceneSelector>,
+
+    /// Name of the glTF KHR_materials_variants variant to apply
+    #[clap(long)]
+    pub material_variant: Option<String>,
+
+    /// Pose the model with an animation, given by index or name, instead of the rest pose
+    #[clap(long, parse(from_str = parse_animation))]
+    pub animation: Option<AnimationSelector>,
+
+    /// Time of the animation pose in seconds, implies --animation
+    #[clap(long, conflicts_with = "animation-position")]
+    pub animation_time: Option<f32>,
+
+    /// Time of the animation pose from 0 (first frame) to 1 (last frame), implies --animation
+    #[clap(long)]
+    pub animation_position: Option<f32>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ArgEnum)]
+pub enum BackendApi {
+    Default,
+    OpenGL,
+    Vulkan,
+    Metal,
+}
+
+impl From<BackendApi> for RendererBackend {
+    fn from(api: BackendApi) -> Self {
+        match api {
+            BackendApi::Default => RendererBackend::Default,
+            BackendApi::OpenGL => RendererBackend::OpenGL,
+            BackendApi::Vulkan => RendererBackend::Vulkan,
+            BackendApi::Metal => RendererBackend::Metal,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ArgEnum)]
+pub enum CameraView {
+    Front,
+    Back,
+    Left,
+    Right,
+    Top,
+    Bottom,
+    Isometric,
+    ThreeQuarter,
+}
+
+impl From<CameraView> for CameraPreset {
+    fn from(view: CameraView) -> Self {
+        match view {
+            CameraView::Front => CameraPreset::Front,
+            CameraView::Back => CameraPreset::Back,
+            CameraView::Left => CameraPreset::Left,
+            CameraView::Right => CameraPreset::Right,
+            CameraView::Top => CameraPreset::Top,
+            CameraView::Bottom => CameraPreset::Bottom,
+            CameraView::Isometric => CameraPreset::Isometric,
+            CameraView::ThreeQuarter => CameraPreset::ThreeQuarter,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ArgEnum)]
+pub enum LightingRig {
+    Sun,
+    ThreePoint,
+    EnvironmentOnly,
+}
+
+impl From<LightingRig> for LightingPreset {
+    fn from(rig: LightingRig) -> Self {
+        match rig {
+            LightingRig::Sun => LightingPreset::Sun,
+            LightingRig::ThreePoint => LightingPreset::ThreePoint,
+            LightingRig::EnvironmentOnly => LightingPreset::EnvironmentOnly,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ArgEnum)]
+pub enum ToneMapper {
+    Aces,
+    AcesLegacy,
+    Filmic,
+    Linear,
+}
+
+impl From<ToneMapper> for ToneMapping {
+    fn from(tone_mapper: ToneMapper) -> Self {
+        match tone_mapper {
+            ToneMapper::Aces => ToneMapping::Aces,
+            ToneMapper::AcesLegacy => ToneMapping::AcesLegacy,
+            ToneMapper::Filmic => ToneMapping::Filmic,
+            ToneMapper::Linear => ToneMapping::Linear,
+        }
+    }
+}
+
+impl OptionArgs {
+    /// Reads the config file if one is given and applies the flags on top of it.
+    pub fn render_options(&self) -> Result<RenderOptions, Box<dyn Error>> {
+        let mut options = match &self.config {
+            Some(path) => read_config(path)?,
+            None => RenderOptions::default(),
+        };
+
+        if let Some(api) = self.api {
+            options.backend = api.into();
+        }
+        options.width = self.width.unwrap_or(options.width);
+        options.height = self.height.unwrap_or(options.height);
+        if let Some(view) = self.camera {
+            options.camera = Some(CameraPreset::from(view).into());
+        }
+        if let Some(embedded_camera) = &self.embedded_camera {
+            options.embedded_camera = embedded_camera.clone();
+        }
+        if self.ignore_embedded_cameras {
+            options.embedded_camera = EmbeddedCamera::Ignore;
+        }
+        options.framing.margin = self.margin.unwrap_or(options.framing.margin);
+        if self.orthographic {
+            options.projection = ProjectionMode::Orthographic;
+        }
+        match &self.environment {
+            Some(EnvironmentSource::Preset(preset)) => {
+                options.environment = Environment::preset(*preset)
+            }
+            Some(source) => {
+                options.environment = Environment {
+                    source: source.clone(),
+                    ..Environment::default()
+                }
+            }
+            None => {}
+        }
+        let environment = &mut options.environment;
+        environment.intensity = self.environment_intensity.unwrap_or(environment.intensity);
+        environment.rotation = self.environment_rotation.unwrap_or(environment.rotation);
+        environment.skybox |= self.skybox;
+        if let Some(rig) = self.lighting {
+            options.lighting = LightingSpec::preset(rig.into());
+        }
+        options.lighting.shadows &= !self.no_shadows;
+        if let Some(exposure) = self.exposure {
+            options.exposure = exposure;
+        }
+        if let Some(tone_mapping) = self.tone_mapping {
+            options.tone_mapping = tone_mapping.into();
+        }
+        if let Some(background) = &self.background {
+            options.background = background.clone();
+        }
+        if self.auto_crop || self.padding.is_some() {
+            let auto_crop = options.auto_crop.get_or_insert_with(AutoCrop::default);
+            auto_crop.padding = self.padding.unwrap_or(auto_crop.padding);
+        }
+        if let Some(scene) = &self.scene {
+            options.scene = Some(scene.clone());
+        }
+        if let Some(variant) = &self.material_variant {
+            options.material_variant = Some(variant.clone());
+        }
+        if self.animation.is_some()
+            || self.animation_time.is_some()
+            || self.animation_position.is_some()
+        {
+            let pose = options.animation.get_or_insert_with(AnimationPose::default);
+            if let Some(animation) = &self.animation {
+                pose.animation = animation.clone();
+            }
+            if let Some(seconds) = self.animation_time {
+                pose.time = AnimationTime::Seconds(seconds);
+            }
+            if let Some(position) = self.animation_position {
+                pose.time = AnimationTime::Normalized(position);
+            }
+        }
+        options.quality.msaa_samples = self.msaa.unwrap_or(options.quality.msaa_samples);
+        options.quality.fxaa &= !self.no_fxaa;
+        options.quality.supersampling = self.supersampling.unwrap_or(options.quality.supersampling);
+
+        Ok(options)
+    }
+}
+
+fn read_config(path: &Path) -> Result<RenderOptions, Box<dyn Error>> {
+    let content = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read config {}: {}", path.display(), err))?;
+    let options = match path.extension().and_then(|e| e.to_str()) {
+        Some(extension) if extension.eq_ignore_ascii_case("json") => serde_json::from_str(&content)
+            .map_err(|err| format!("invalid config {}: {}", path.display(), err))?,
+        _ => toml::from_str(&content)
+            .map_err(|err| format!("invalid config {}: {}", path.display(), err))?,
+    };
+    Ok(options)
+}
+
+fn parse_background(value: &str) -> Result<Background, String> {
+    if value.eq_ignore_ascii_case("transparent") {
+        Ok(Background::Transparent)
+    } else if let Some((top, bottom)) = value.split_once(',') {
+        Ok(Background::Gradient {
+            top: parse_color(top)?,
+            bottom: parse_color(bottom)?,
+        })
+    } else if value.starts_with('#') {
+        Ok(Background::Solid(parse_color(value)?))
+    } else {
+        Ok(Background::ImageFile(PathBuf::from(value)))
+    }
+}
+
+fn parse_exposure(value: &str) -> Result<Exposure, String> {
+    let number = |value: &str| {
+        let value = value.trim();
+        match value.split_once('/') {
+            Some((numerator, denominator)) => numerator
+                .parse::<f32>()
+                .and_then(|numerator| Ok(numerator / denominator.parse::<f32>()?)),
+            None => value.parse::<f32>(),
+        }
+        .map_err(|_| format!("invalid exposure {}", value))
+    };
+
+    if let Some(compensation) = value.strip_prefix("auto") {
+        Ok(Exposure::Auto {
+            compensation: match compensation.strip_prefix(':') {
+                Some(compensation) => number(compensation)?,
+                None if compensation.is_empty() => 0.0,
+                None => return Err(format!("invalid exposure {}", value)),
+            },
+        })
+    } else if let [aperture, shutter_speed, sensitivity] =
+        value.split(',').collect::<Vec<_>>().as_slice()
+    {
+        Ok(Exposure::Physical {
+            aperture: number(aperture)?,
+            shutter_speed: number(shutter_speed)?,
+            sensitivity: number(sensitivity)?,
+        })
+    } else {
+        Ok(Exposure::Ev100(number(value)?))
+    }
+}
+
+fn parse_animation(value: &str) -> AnimationSelector {
+    match value.parse() {
+        Ok(index) => AnimationSelector::Index(index),
+        Err(_) => AnimationSelector::Name(value.to_owned()),
+    }
+}
+
+fn parse_embedded_camera(value: &str) -> EmbeddedCamera {
+    match value.parse() {
+        Ok(index) => EmbeddedCamera::Index(index),
+        Err(_) => EmbeddedCamera::Name(value.to_owned()),
+    }
+}
+
+fn parse_scene(value: &str) -> SceneSelector {
+    match value.parse() {
+        Ok(index) => SceneSelector::Index(index),
+        Err(_) => SceneSelector::Name(value.to_owned()),
+    }
+}
+
+fn parse_environment(value: &str) -> EnvironmentSource {
+    match value.to_ascii_lowercase().as_str() {
+        "studio" => EnvironmentSource::Preset(EnvironmentPreset::Studio),
+        "outdoor" => EnvironmentSource::Preset(EnvironmentPreset::Outdoor),
+        "neutral-grey" | "neutral-gray" => {
+            EnvironmentSource::Preset(EnvironmentPreset::NeutralGrey)
+        }
+        _ => EnvironmentSource::KtxFile(PathBuf::from(value)),
+    }
+}
+
+fn parse_color(value: &str) -> Result<[u8; 3], String> {
+    let hex = value.trim().trim_start_matches('#');
+    let channel = |index: usize| {
+        hex.get(index..index + 2)
+            .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+            .ok_or_else(|| format!("invalid color {}, expected #rrggbb", value))
+    };
+    if hex.len() != 6 {
+        return Err(format!("invalid color {}, expected #rrggbb", value));
+    }
+    Ok([channel(0)?, channel(2)?, channel(4)?])
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use space_thumbnails::{Background, Exposure};
+
+    use super::{parse_background, parse_color, parse_exposure};
+
+    #[test]
+    fn colors() {
+        assert_eq!(parse_color("#20a0Ff"), Ok([0x20, 0xa0, 0xff]));
+        assert_eq!(parse_color(" 000000 "), Ok([0, 0, 0]));
+        assert!(parse_color("#fff").is_err());
+        assert!(parse_color("#12345g").is_err());
+        assert!(parse_color("#1234567").is_err());
+        assert!(parse_color("#ééé").is_err());
+    }
+
+    #[test]
+    fn backgrounds() {
+        assert_eq!(parse_background("Transparent"), Ok(Background::Transparent));
+        assert_eq!(
+            parse_background("#202020"),
+            Ok(Background::Solid([0x20, 0x20, 0x20]))
+        );
+        assert_eq!(
+            parse_background("#ffffff,#c0c0c0"),
+            Ok(Background::Gradient {
+                top: [0xff, 0xff, 0xff],
+                bottom: [0xc0, 0xc0, 0xc0],
+            })
+        );
+        assert_eq!(
+            parse_background("backdrops/studio.png"),
+            Ok(Background::ImageFile(PathBuf::from("backdrops/studio.png")))
+        );
+        assert!(parse_background("#2020").is_err());
+        assert!(parse_background("#ffffff,white").is_err());
+    }
+
+    #[test]
+    fn exposures() {
+        assert_eq!(parse_exposure("15"), Ok(Exposure::Ev100(15.0)));
+        assert_eq!(
+            parse_exposure("16,1/125,100"),
+            Ok(Exposure::Physical {
+                aperture: 16.0,
+                shutter_speed: 1.0 / 125.0,
+                sensitivity: 100.0,
+            })
+        );
+        assert_eq!(
+            parse_exposure("auto"),
+            Ok(Exposure::Auto { compensation: 0.0 })
+        );
+        assert_eq!(
+            parse_exposure("auto:+1"),
+            Ok(Exposure::Auto { compensation: 1.0 })
+        );
+        assert_eq!(
+            parse_exposure("auto:-0.5"),
+            Ok(Exposure::Auto { compensation: -0.5 })
+        );
+        assert!(parse_exposure("bright").is_err());
+        assert!(parse_exposure("auto+1").is_err());
+        assert!(parse_exposure("auto:").is_err());
+        assert!(parse_exposure("16,1/0x,100").is_err());
+        assert!(parse_exposure("16,1/125").is_err());
+    }
+}
@@ -0,0 +1,252 @@
+use std::io::Write;
+
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    Delay, Frame, RgbaImage,
+};
+
+use crate::{AnimationSelector, Result, SpaceThumbnailsError};
+
+/// One frame of an animated thumbnail.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationFrame {
+    pub image: RgbaImage,
+    /// How long the frame is shown, in milliseconds.
+    pub delay_ms: u32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AnimationFormat {
+    /// Animated PNG.
+    Png,
+    Gif,
+    /// Animated WebP, requires the `webp` feature.
+    WebP,
+}
+
+impl AnimationFormat {
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "png" | "apng" => Some(Self::Png),
+            "gif" => Some(Self::Gif),
+            "webp" => Some(Self::WebP),
+            _ => None,
+        }
+    }
+}
+
+/// Orbit of the camera around the model, the model is framed once for the whole orbit so it
+/// keeps the same size in every frame.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TurntableOptions {
+    pub frames: u32,
+    pub degrees_per_frame: f32,
+    /// Azimuth of the first frame in degrees.
+    pub start_azimuth: f32,
+    /// Elevation of the camera in degrees.
+    pub elevation: f32,
+    /// How long each frame is shown, in milliseconds.
+    pub frame_delay_ms: u32,
+}
+
+impl Default for TurntableOptions {
+    fn default() -> Self {
+        Self {
+            frames: 36,
+            degrees_per_frame: 10.0,
+            start_azimuth: 45.0,
+            elevation: 20.0,
+            frame_delay_ms: 50,
+        }
+    }
+}
+
+/// Playback of one of the animations of the model, seen from a fixed camera. The model is framed
+/// once for the bounds it covers during the whole animation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaybackOptions {
+    pub animation: AnimationSelector,
+    /// Sampled frames per second.
+    pub frame_rate: f32,
+    /// Long animations are sampled at a lower rate to stay under this number of frames.
+    pub max_frames: u32,
+}
+
+impl Default for PlaybackOptions {
+    fn default() -> Self {
+        Self {
+            animation: AnimationSelector::default(),
+            frame_rate: 15.0,
+            max_frames: 150,
+        }
+    }
+}
+
+impl PlaybackOptions {
+    /// Sample times in seconds and the delay between frames in milliseconds for an animation of
+    /// `duration` seconds. The end of the animation is left out as it usually loops back to the
+    /// first frame.
+    pub(crate) fn sample_times(&self, duration: f32) -> (Vec<f32>, u32) {
+        let frames = (duration * self.frame_rate.max(0.0))
+            .round()
+            .clamp(1.0, self.max_frames.max(1) as f32) as u32;
+        let step = duration / frames as f32;
+        let times = (0..frames).map(|index| index as f32 * step).collect();
+        (times, (step * 1000.0).round().max(1.0) as u32)
+    }
+}
+
+/// Encodes `frames` as an infinitely looping animation. All frames must have the same size.
+pub fn encode_animation(
+    frames: &[AnimationFrame],
+    format: AnimationFormat,
+    writer: impl Write,
+) -> Result<()> {
+    match format {
+        AnimationFormat::Png => encode_png(frames, writer),
+        AnimationFormat::Gif => encode_gif(frames, writer),
+        AnimationFormat::WebP => encode_webp(frames, writer),
+    }
+}
+
+fn encode_png(frames: &[AnimationFrame], writer: impl Write) -> Result<()> {
+    let (width, height) = frames
+        .first()
+        .map(|frame| frame.image.dimensions())
+        .unwrap_or((1, 1));
+    let mut encoder = png::Encoder::new(writer, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .set_animated(frames.len() as u32, 0)
+        .map_err(encode_error)?;
+    let mut writer = encoder.write_header().map_err(encode_error)?;
+    for frame in frames {
+        writer
+            .set_frame_delay(frame.delay_ms.min(u16::MAX as u32) as u16, 1000)
+            .map_err(encode_error)?;
+        writer
+            .write_image_data(frame.image.as_raw())
+            .map_err(encode_error)?;
+    }
+    writer.finish().map_err(encode_error)
+}
+
+fn encode_gif(frames: &[AnimationFrame], writer: impl Write) -> Result<()> {
+    let mut encoder = GifEncoder::new(writer);
+    encoder.set_repeat(Repeat::Infinite).map_err(encode_error)?;
+    encoder
+        .encode_frames(frames.iter().map(|frame| {
+            Frame::from_parts(
+                frame.image.clone(),
+                0,
+                0,
+                Delay::from_numer_denom_ms(frame.delay_ms, 1),
+            )
+        }))
+        .map_err(encode_error)
+}
+
+#[cfg(feature = "webp")]
+fn encode_webp(frames: &[AnimationFrame], mut writer: impl Write) -> Result<()> {
+    let dimensions = frames
+        .first()
+        .map(|frame| frame.image.dimensions())
+        .unwrap_or((1, 1));
+    let webp_error = |err: webp_animation::Error| encode_error(format!("{:?}", err));
+    let mut encoder = webp_animation::Encoder::new(dimensions).map_err(webp_error)?;
+    let mut timestamp = 0;
+    for frame in frames {
+        encoder
+            .add_frame(frame.image.as_raw(), timestamp)
+            .map_err(webp_error)?;
+        timestamp += frame.delay_ms as i32;
+    }
+    let data = encoder.finalize(timestamp).map_err(webp_error)?;
+    writer.write_all(&data).map_err(encode_error)
+}
+
+#[cfg(not(feature = "webp"))]
+fn encode_webp(_frames: &[AnimationFrame], _writer: impl Write) -> Result<()> {
+    Err(SpaceThumbnailsError::UnsupportedFormat(
+        "webp (enable the `webp` feature)".to_owned(),
+    ))
+}
+
+fn encode_error(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> SpaceThumbnailsError {
+    SpaceThumbnailsError::Encode(err.into())
+}
+
+#[cfg(test)]
+mod test {
+    use image::{codecs::gif::GifDecoder, AnimationDecoder, Rgba, RgbaImage};
+
+    use super::{encode_animation, AnimationFormat, AnimationFrame, PlaybackOptions};
+
+    fn frames(count: u8) -> Vec<AnimationFrame> {
+        (0..count)
+            .map(|index| AnimationFrame {
+                image: RgbaImage::from_pixel(4, 3, Rgba([index * 40, 0, 0, 255])),
+                delay_ms: 50,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn encode_gif_frames() {
+        let mut data = Vec::new();
+        encode_animation(&frames(3), AnimationFormat::Gif, &mut data).unwrap();
+        let decoded = GifDecoder::new(data.as_slice())
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap();
+        assert_eq!(decoded.len(), 3);
+        assert_eq!(decoded[0].buffer().dimensions(), (4, 3));
+        assert_eq!(decoded[0].delay().numer_denom_ms(), (50, 1));
+    }
+
+    #[test]
+    fn encode_apng_frames() {
+        let mut data = Vec::new();
+        encode_animation(&frames(3), AnimationFormat::Png, &mut data).unwrap();
+        let reader = png::Decoder::new(data.as_slice()).read_info().unwrap();
+        let info = reader.info();
+        assert_eq!((info.width, info.height), (4, 3));
+        let control = info.animation_control.unwrap();
+        assert_eq!((control.num_frames, control.num_plays), (3, 0));
+    }
+
+    #[test]
+    fn playback_sample_times() {
+        let options = PlaybackOptions {
+            frame_rate: 4.0,
+            ..PlaybackOptions::default()
+        };
+        assert_eq!(options.sample_times(1.0), (vec![0.0, 0.25, 0.5, 0.75], 250));
+
+        let options = PlaybackOptions {
+            max_frames: 2,
+            ..options
+        };
+        assert_eq!(options.sample_times(1.0), (vec![0.0, 0.5], 500));
+        assert_eq!(options.sample_times(0.0), (vec![0.0], 1));
+    }
+
+    #[test]
+    fn playback_of_short_animations() {
+        let options = PlaybackOptions {
+            frame_rate: 4.0,
+            ..PlaybackOptions::default()
+        };
+        // shorter than a frame, a single frame covers the whole animation
+        assert_eq!(options.sample_times(0.1), (vec![0.0], 100));
+        let still = PlaybackOptions {
+            frame_rate: 0.0,
+            max_frames: 0,
+            ..options
+        };
+        assert_eq!(still.sample_times(2.0), (vec![0.0], 2000));
+        assert_eq!(still.sample_times(0.0), (vec![0.0], 1));
+    }
+}
@@ -0,0 +1,222 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs::File,
+    io::{self, Cursor, Read, Seek},
+    path::{Path, PathBuf},
+};
+
+use zip::ZipArchive;
+
+use crate::{
+    vfs::{normalize_path, Vfs, LIMITS},
+    Result, SpaceThumbnailsError,
+};
+
+/// Extensions of the entries considered models, in order of preference.
+const MODEL_EXTENSIONS: &[&str] = &[
+    "glb", "gltf", "obj", "fbx", "stl", "dae", "ply", "x3d", "x3db", "3ds", "blend", "ase", "ifc",
+    "lwo", "lws", "lxo", "ac", "ms3d", "cob", "md2", "md3", "md5mesh", "mdl", "mdc", "x", "off",
+    "b3d", "q3d", "q3s", "nff", "enff", "smd", "irr", "irrmesh", "ogex", "3mf", "amf", "dxf",
+    "xgl", "zgl", "hmp", "iqm", "m3d", "csm", "ter", "sib", "ndo", "ask", "bvh",
+];
+
+/// Where a ZIP archive is read from.
+#[derive(Debug, Copy, Clone)]
+pub enum ArchiveSource<'a> {
+    File(&'a Path),
+    Memory(&'a [u8]),
+}
+
+impl<'a> From<&'a Path> for ArchiveSource<'a> {
+    fn from(path: &'a Path) -> Self {
+        Self::File(path)
+    }
+}
+
+impl<'a> From<&'a PathBuf> for ArchiveSource<'a> {
+    fn from(path: &'a PathBuf) -> Self {
+        Self::File(path)
+    }
+}
+
+impl<'a> From<&'a [u8]> for ArchiveSource<'a> {
+    fn from(data: &'a [u8]) -> Self {
+        Self::Memory(data)
+    }
+}
+
+impl<'a> From<&'a Vec<u8>> for ArchiveSource<'a> {
+    fn from(data: &'a Vec<u8>) -> Self {
+        Self::Memory(data)
+    }
+}
+
+trait ReadSeek: Read + Seek {}
+
+impl<T: Read + Seek> ReadSeek for T {}
+
+/// An opened ZIP archive, entries are decompressed when they are opened.
+pub(crate) struct Archive {
+    zip: RefCell<ZipArchive<Box<dyn ReadSeek + 'static>>>,
+    entries: HashMap<PathBuf, usize>,
+}
+
+impl Archive {
+    pub fn open(source: ArchiveSource) -> Result<Self> {
+        let reader: Box<dyn ReadSeek> = match source {
+            ArchiveSource::File(path) => {
+                Box::new(File::open(path).map_err(|source| SpaceThumbnailsError::Io {
+                    path: path.to_owned(),
+                    source,
+                })?)
+            }
+            ArchiveSource::Memory(data) => Box::new(Cursor::new(data.to_vec())),
+        };
+        let mut zip = ZipArchive::new(reader).map_err(corrupt_archive)?;
+        let mut entries = HashMap::new();
+        for index in 0..zip.len() {
+            let entry = zip.by_index(index).map_err(corrupt_archive)?;
+            if !entry.is_file() {
+                continue;
+            }
+            // entries escaping the archive root are skipped
+            if let Some(path) = normalize_path(Path::new(entry.name())) {
+                entries.insert(path, index);
+            }
+        }
+        Ok(Self {
+            zip: RefCell::new(zip),
+            entries,
+        })
+    }
+
+    fn index(&self, path: &Path) -> io::Result<usize> {
+        normalize_path(path)
+            .and_then(|path| self.entries.get(&path).copied())
+            .ok_or_else(|| io::ErrorKind::NotFound.into())
+    }
+
+    /// The model to load when no entry is given, the first format of `MODEL_EXTENSIONS` and the
+    /// least nested entry when several have the same format.
+    pub fn primary_model(&self) -> Option<PathBuf> {
+        let hidden = |path: &Path| {
+            path.components().any(|component| {
+                let name = component.as_os_str().to_string_lossy();
+                name == "__MACOSX" || name.starts_with('.')
+            })
+        };
+        self.entries
+            .keys()
+            .filter(|path| !hidden(path))
+            .filter_map(|path| {
+                let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+                let rank = MODEL_EXTENSIONS
+                    .iter()
+                    .position(|supported| *supported == extension)?;
+                Some((rank, path.components().count(), path))
+            })
+            .min()
+            .map(|(_, _, path)| path.clone())
+    }
+}
+
+impl Vfs for Archive {
+    fn open(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let index = self.index(path)?;
+        let mut zip = self.zip.borrow_mut();
+        let entry = zip
+            .by_index(index)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let size = entry.size();
+        if size > LIMITS.bytes {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "archive entry is too large",
+            ));
+        }
+        let mut data = Vec::with_capacity(size as usize);
+        // never decompress more than the announced size
+        entry.take(size + 1).read_to_end(&mut data)?;
+        if data.len() as u64 > size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "archive entry is larger than announced",
+            ));
+        }
+        Ok(data)
+    }
+
+    fn list(&self) -> Option<Vec<PathBuf>> {
+        Some(self.entries.keys().cloned().collect())
+    }
+
+    fn size(&self, path: &Path) -> Option<u64> {
+        let index = self.index(path).ok()?;
+        let mut zip = self.zip.borrow_mut();
+        let entry = zip.by_index_raw(index).ok()?;
+        Some(entry.size())
+    }
+}
+
+fn corrupt_archive(err: zip::result::ZipError) -> SpaceThumbnailsError {
+    SpaceThumbnailsError::CorruptAsset {
+        format: "zip".to_owned(),
+        source: err.into(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{Cursor, Write},
+        path::{Path, PathBuf},
+    };
+
+    use zip::{write::FileOptions, ZipWriter};
+
+    use crate::vfs::Vfs;
+
+    use super::{Archive, ArchiveSource};
+
+    fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, data) in files {
+            writer.start_file(*name, FileOptions::default()).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn primary_model_prefers_gltf() {
+        let data = zip(&[
+            ("__MACOSX/scene.gltf", b""),
+            ("textures/wood.png", b""),
+            ("extra/chair.fbx", b""),
+            ("models/chair.gltf", b"{}"),
+            ("chair.bin", b""),
+        ]);
+        let archive = Archive::open(ArchiveSource::from(&data)).unwrap();
+        assert_eq!(
+            archive.primary_model(),
+            Some(PathBuf::from("models/chair.gltf"))
+        );
+        assert_eq!(
+            archive
+                .open(Path::new("models/../models/chair.gltf"))
+                .unwrap(),
+            b"{}"
+        );
+        assert!(archive.open(Path::new("missing.bin")).is_err());
+        assert_eq!(archive.size(Path::new("models/chair.gltf")), Some(2));
+    }
+
+    #[test]
+    fn no_model() {
+        let data = zip(&[("readme.txt", b"")]);
+        let archive = Archive::open(ArchiveSource::from(&data)).unwrap();
+        assert_eq!(archive.primary_model(), None);
+        assert!(Archive::open(ArchiveSource::Memory(b"not a zip")).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+use std::{borrow::Cow, path::PathBuf};
+
+use image::{imageops::FilterType, DynamicImage, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use crate::{Result, SpaceThumbnailsError};
+
+/// What the rendered model is composited over. Colors are 8-bit sRGB.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Background {
+    #[default]
+    Transparent,
+    Solid([u8; 3]),
+    /// Vertical gradient from the top to the bottom of the image.
+    Gradient {
+        top: [u8; 3],
+        bottom: [u8; 3],
+    },
+    /// Backdrop scaled to cover the whole image, cropping the overflowing edges.
+    #[serde(skip)]
+    Image(RgbaImage),
+    /// Like `Image`, loaded from a file when the background is set.
+    ImageFile(PathBuf),
+}
+
+impl Background {
+    /// Reads the backdrop of `ImageFile`, other backgrounds are returned unchanged.
+    pub(crate) fn load(&self) -> Result<Background> {
+        match self {
+            Background::ImageFile(path) => image::open(path)
+                .map(|image| Background::Image(image.into_rgba8()))
+                .map_err(|source| SpaceThumbnailsError::ImageLoad {
+                    path: path.clone(),
+                    source,
+                }),
+            background => Ok(background.clone()),
+        }
+    }
+
+    /// Scales the backdrop of `Image` to cover a `width` x `height` image, other backgrounds are
+    /// returned unchanged. Compositing the result at that size does not resize it again.
+    pub(crate) fn fit(&self, width: u32, height: u32) -> Background {
+        match self {
+            Background::Image(image) => Background::Image(fit_image(image, width, height).into_owned()),
+            background => background.clone(),
+        }
+    }
+
+    /// Composites the straight alpha RGBA `pixels` of a `width` x `height` image over this
+    /// background in place.
+    pub(crate) fn composite(&self, pixels: &mut [u8], width: u32, height: u32) {
+        let backdrop = match self {
+            Background::Transparent => return,
+            Background::Image(image) => Some(fit_image(image, width, height)),
+            _ => None,
+        };
+
+        for (index, pixel) in pixels
+            .chunks_exact_mut(4)
+            .take((width * height) as usize)
+            .enumerate()
+        {
+            let x = index as u32 % width;
+            let y = index as u32 / width;
+            let under = match (self, &backdrop) {
+                (_, Some(backdrop)) => backdrop.get_pixel(x, y).0,
+                (Background::Solid([r, g, b]), _) => [*r, *g, *b, 255],
+                (Background::Gradient { top, bottom }, _) => {
+                    let t = if height > 1 {
+                        y as f32 / (height - 1) as f32
+                    } else {
+                        0.0
+                    };
+                    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+                    [
+                        mix(top[0], bottom[0]),
+                        mix(top[1], bottom[1]),
+                        mix(top[2], bottom[2]),
+                        255,
+                    ]
+                }
+                _ => return,
+            };
+            over(pixel, under);
+        }
+    }
+}
+
+fn fit_image(image: &RgbaImage, width: u32, height: u32) -> Cow<'_, RgbaImage> {
+    if image.dimensions() == (width, height) {
+        return Cow::Borrowed(image);
+    }
+    Cow::Owned(
+        DynamicImage::ImageRgba8(image.clone())
+            .resize_to_fill(width, height, FilterType::Triangle)
+            .into_rgba8(),
+    )
+}
+
+/// Straight alpha "over" operator, writes `pixel` over `under` into `pixel`.
+fn over(pixel: &mut [u8], under: [u8; 4]) {
+    let alpha = pixel[3] as f32 / 255.0;
+    let under_alpha = under[3] as f32 / 255.0 * (1.0 - alpha);
+    let out_alpha = alpha + under_alpha;
+    if out_alpha <= 0.0 {
+        pixel.copy_from_slice(&[0, 0, 0, 0]);
+        return;
+    }
+    for channel in 0..3 {
+        pixel[channel] = ((pixel[channel] as f32 * alpha + under[channel] as f32 * under_alpha)
+            / out_alpha)
+            .round() as u8;
+    }
+    pixel[3] = (out_alpha * 255.0).round() as u8;
+}
+
+#[cfg(test)]
+mod test {
+    use image::{Rgba, RgbaImage};
+
+    use super::{over, Background};
+
+    #[test]
+    fn over_operator() {
+        let mut opaque = [10, 20, 30, 255];
+        over(&mut opaque, [200, 200, 200, 255]);
+        assert_eq!(opaque, [10, 20, 30, 255]);
+
+        let mut transparent = [10, 20, 30, 0];
+        over(&mut transparent, [200, 100, 50, 255]);
+        assert_eq!(transparent, [200, 100, 50, 255]);
+
+        let mut half = [255, 0, 0, 128];
+        over(&mut half, [0, 0, 255, 255]);
+        assert_eq!(half, [128, 0, 127, 255]);
+
+        let mut nothing = [10, 20, 30, 0];
+        over(&mut nothing, [200, 100, 50, 0]);
+        assert_eq!(nothing, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn composite_backgrounds() {
+        let pixels = [[0, 0, 0, 0], [50, 60, 70, 255], [0, 0, 0, 0], [0, 0, 0, 0]].concat();
+
+        let mut transparent = pixels.clone();
+        Background::Transparent.composite(&mut transparent, 2, 2);
+        assert_eq!(transparent, pixels);
+
+        let mut solid = pixels.clone();
+        Background::Solid([1, 2, 3]).composite(&mut solid, 2, 2);
+        assert_eq!(
+            solid,
+            [
+                [1, 2, 3, 255],
+                [50, 60, 70, 255],
+                [1, 2, 3, 255],
+                [1, 2, 3, 255]
+            ]
+            .concat()
+        );
+
+        let mut gradient = pixels.clone();
+        Background::Gradient {
+            top: [0, 0, 0],
+            bottom: [255, 255, 255],
+        }
+        .composite(&mut gradient, 2, 2);
+        assert_eq!(&gradient[..4], &[0, 0, 0, 255]);
+        assert_eq!(&gradient[4..8], &[50, 60, 70, 255]);
+        assert_eq!(
+            &gradient[8..],
+            &[[255, 255, 255, 255], [255, 255, 255, 255]].concat()[..]
+        );
+
+        let mut image = pixels;
+        Background::Image(RgbaImage::from_pixel(4, 4, Rgba([9, 8, 7, 255])))
+            .composite(&mut image, 2, 2);
+        assert_eq!(&image[..4], &[9, 8, 7, 255]);
+        assert_eq!(&image[4..8], &[50, 60, 70, 255]);
+    }
+
+    #[test]
+    fn fit_backdrop() {
+        let backdrop = Background::Image(RgbaImage::from_pixel(8, 4, Rgba([9, 8, 7, 255])));
+        match backdrop.fit(3, 3) {
+            Background::Image(image) => {
+                assert_eq!(image.dimensions(), (3, 3));
+                assert_eq!(image.get_pixel(1, 1), &Rgba([9, 8, 7, 255]));
+            }
+            background => panic!("unexpected background {:?}", background),
+        }
+        assert_eq!(
+            Background::Solid([1, 2, 3]).fit(3, 3),
+            Background::Solid([1, 2, 3])
+        );
+    }
+}
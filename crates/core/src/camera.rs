@@ -0,0 +1,352 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bounds::{add, length, normalize, scale, sub, Bounds},
+    framing::{Framing, ViewBasis},
+};
+
+/// Direction of the legacy surround view, kept as the default three-quarter viewpoint.
+const THREE_QUARTER_DIRECTION: [f32; 3] = [2.5, 1.7, 2.5];
+
+/// Focal length of the default lens, in millimeters.
+pub(crate) const FOCAL_LENGTH: f64 = 28.0;
+
+/// Vertical field of view in radians of a lens on a 24mm tall sensor, matching
+/// `Camera::set_lens_projection`.
+pub(crate) fn lens_vertical_fov(focal_length: f64) -> f32 {
+    (2.0 * (12.0 / focal_length).atan()) as f32
+}
+
+/// Named viewpoints, all looking at the center of the model.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CameraPreset {
+    Front,
+    Back,
+    Left,
+    Right,
+    Top,
+    Bottom,
+    Isometric,
+    ThreeQuarter,
+}
+
+/// Describes where the camera is placed.
+///
+/// Positions are expressed in the normalized model space, where the loaded model is scaled into
+/// the `[-1, 1]` cube centered at the origin. `+Y` is up and the front of the model faces `+Z`.
+///
+/// In configuration files a preset is written as its name, e.g. `"front"`, and the other variants
+/// as tables of their fields.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CameraSpec {
+    Preset(CameraPreset),
+    /// Orbit around the model center. Angles are in degrees, `azimuth` turns from `+Z` towards
+    /// `+X` and `elevation` is measured from the horizontal plane. When `distance` is `None` the
+    /// camera is moved back until the model is framed.
+    Orbit {
+        azimuth: f32,
+        elevation: f32,
+        #[serde(default)]
+        distance: Option<f32>,
+    },
+    /// Fully custom camera.
+    LookAt {
+        eye: [f32; 3],
+        target: [f32; 3],
+        up: [f32; 3],
+    },
+}
+
+impl Default for CameraSpec {
+    fn default() -> Self {
+        Self::Preset(CameraPreset::ThreeQuarter)
+    }
+}
+
+impl From<CameraPreset> for CameraSpec {
+    fn from(preset: CameraPreset) -> Self {
+        Self::Preset(preset)
+    }
+}
+
+impl CameraPreset {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CameraPreset::Front => "front",
+            CameraPreset::Back => "back",
+            CameraPreset::Left => "left",
+            CameraPreset::Right => "right",
+            CameraPreset::Top => "top",
+            CameraPreset::Bottom => "bottom",
+            CameraPreset::Isometric => "isometric",
+            CameraPreset::ThreeQuarter => "three-quarter",
+        }
+    }
+
+    pub fn orbit(&self) -> (f32, f32) {
+        match self {
+            CameraPreset::Front => (0.0, 0.0),
+            CameraPreset::Back => (180.0, 0.0),
+            CameraPreset::Left => (-90.0, 0.0),
+            CameraPreset::Right => (90.0, 0.0),
+            CameraPreset::Top => (0.0, 90.0),
+            CameraPreset::Bottom => (0.0, -90.0),
+            // elevation of the cube diagonal, atan(1 / sqrt(2))
+            CameraPreset::Isometric => (45.0, 35.264_39),
+            CameraPreset::ThreeQuarter => {
+                let [x, y, z] = THREE_QUARTER_DIRECTION;
+                (45.0, y.atan2((x * x + z * z).sqrt()).to_degrees())
+            }
+        }
+    }
+}
+
+/// Projection used for the generated camera. Cameras stored in the model file keep their own
+/// projection unless `Orthographic` is forced.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProjectionMode {
+    #[default]
+    Perspective,
+    Orthographic,
+}
+
+/// Which camera stored in the model file is used when no camera is set. glTF cameras are looked
+/// up by their index or name in the file, the assimp loader only exposes the main camera as
+/// `"camera 0"`.
+///
+/// In configuration files `First` and `Ignore` are written as `"first"` and `"ignore"`, the
+/// other variants as e.g. `{ name = "hero" }`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EmbeddedCamera {
+    /// The first camera of the scene, if there is one.
+    #[default]
+    First,
+    Index(usize),
+    Name(String),
+    /// Always use a generated camera.
+    Ignore,
+}
+
+impl fmt::Display for EmbeddedCamera {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::First => write!(f, "first"),
+            Self::Index(index) => write!(f, "#{}", index),
+            Self::Name(name) => write!(f, "\"{}\"", name),
+            Self::Ignore => write!(f, "ignore"),
+        }
+    }
+}
+
+/// A resolved camera placement.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CameraPose {
+    pub eye: [f32; 3],
+    pub target: [f32; 3],
+    pub up: [f32; 3],
+}
+
+/// A resolved orthographic camera, the frustum is centered on the view axis.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct OrthographicView {
+    pub pose: CameraPose,
+    pub half_width: f32,
+    pub half_height: f32,
+    pub near: f32,
+    pub far: f32,
+}
+
+impl CameraSpec {
+    /// Returns the unit direction from the target to the eye and the up vector, or `None` for
+    /// a `LookAt` camera.
+    pub fn direction(&self) -> Option<([f32; 3], [f32; 3])> {
+        let (azimuth, elevation) = match self {
+            CameraSpec::Preset(preset) => preset.orbit(),
+            CameraSpec::Orbit {
+                azimuth, elevation, ..
+            } => (*azimuth, *elevation),
+            CameraSpec::LookAt { .. } => return None,
+        };
+        Some(orbit_direction(azimuth, elevation))
+    }
+
+    /// Places the camera so that it looks at `bounds` from this viewpoint. Unless an explicit
+    /// distance is given, the camera is moved back until `framing` is satisfied for a perspective
+    /// projection with the given aspect ratio and vertical field of view in radians.
+    pub fn resolve(
+        &self,
+        bounds: &Bounds,
+        framing: &Framing,
+        aspect: f32,
+        vertical_fov: f32,
+    ) -> CameraPose {
+        let ((direction, up), distance) = match *self {
+            CameraSpec::LookAt { eye, target, up } => return CameraPose { eye, target, up },
+            CameraSpec::Orbit {
+                azimuth,
+                elevation,
+                distance,
+            } => (orbit_direction(azimuth, elevation), distance),
+            CameraSpec::Preset(preset) => {
+                let (azimuth, elevation) = preset.orbit();
+                (orbit_direction(azimuth, elevation), None)
+            }
+        };
+        let distance = distance.unwrap_or_else(|| {
+            framing.perspective_distance(
+                bounds,
+                &ViewBasis::new(direction, up),
+                aspect,
+                vertical_fov,
+            )
+        });
+        let target = bounds.center();
+        CameraPose {
+            eye: add(target, scale(direction, distance)),
+            target,
+            up,
+        }
+    }
+
+    /// Places an orthographic camera looking at `bounds` from this viewpoint and sizes the
+    /// frustum so that `framing` is satisfied.
+    pub(crate) fn resolve_orthographic(
+        &self,
+        bounds: &Bounds,
+        framing: &Framing,
+        aspect: f32,
+    ) -> OrthographicView {
+        let radius = length(bounds.half_extent());
+        let (pose, backward) = match *self {
+            CameraSpec::LookAt { eye, target, up } => {
+                (CameraPose { eye, target, up }, normalize(sub(eye, target)))
+            }
+            _ => {
+                let (direction, up) = self.direction().unwrap_or(orbit_direction(0.0, 0.0));
+                let distance = match *self {
+                    CameraSpec::Orbit {
+                        distance: Some(distance),
+                        ..
+                    } => distance,
+                    // the distance does not change the size of the model, just stay outside
+                    _ => radius * 2.0,
+                };
+                let target = bounds.center();
+                let pose = CameraPose {
+                    eye: add(target, scale(direction, distance)),
+                    target,
+                    up,
+                };
+                (pose, direction)
+            }
+        };
+        let basis = ViewBasis::new(backward, pose.up);
+        let (half_width, half_height) =
+            framing.orthographic_extent(bounds, pose.target, &basis, aspect);
+        let eye_to_center = length(sub(bounds.center(), pose.eye));
+        OrthographicView {
+            pose,
+            half_width,
+            half_height,
+            near: 0.01,
+            far: eye_to_center + radius * 2.0,
+        }
+    }
+}
+
+fn orbit_direction(azimuth: f32, elevation: f32) -> ([f32; 3], [f32; 3]) {
+    let (azimuth, elevation) = (azimuth.to_radians(), elevation.to_radians());
+    let direction = [
+        elevation.cos() * azimuth.sin(),
+        elevation.sin(),
+        elevation.cos() * azimuth.cos(),
+    ];
+    // looking straight down or up, the world up axis is degenerate so derive it from the azimuth
+    let up = if elevation.cos().abs() < 1e-4 {
+        let forward = [-azimuth.sin(), 0.0, -azimuth.cos()];
+        if elevation > 0.0 {
+            forward
+        } else {
+            scale(forward, -1.0)
+        }
+    } else {
+        [0.0, 1.0, 0.0]
+    };
+    (direction, up)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        bounds::{normalize, Bounds},
+        framing::Framing,
+    };
+
+    use super::{CameraPreset, CameraSpec, THREE_QUARTER_DIRECTION};
+
+    fn assert_close(actual: [f32; 3], expected: [f32; 3]) {
+        for axis in 0..3 {
+            assert!(
+                (actual[axis] - expected[axis]).abs() < 1e-4,
+                "{:?} != {:?}",
+                actual,
+                expected
+            );
+        }
+    }
+
+    fn direction(preset: CameraPreset) -> ([f32; 3], [f32; 3]) {
+        CameraSpec::Preset(preset).direction().unwrap()
+    }
+
+    #[test]
+    fn preset_directions() {
+        let (front, up) = direction(CameraPreset::Front);
+        assert_close(front, [0.0, 0.0, 1.0]);
+        assert_close(up, [0.0, 1.0, 0.0]);
+        assert_close(direction(CameraPreset::Back).0, [0.0, 0.0, -1.0]);
+        assert_close(direction(CameraPreset::Right).0, [1.0, 0.0, 0.0]);
+        assert_close(direction(CameraPreset::Left).0, [-1.0, 0.0, 0.0]);
+        assert_close(
+            direction(CameraPreset::Isometric).0,
+            normalize([1.0, 1.0, 1.0]),
+        );
+        assert_close(
+            direction(CameraPreset::ThreeQuarter).0,
+            normalize(THREE_QUARTER_DIRECTION),
+        );
+    }
+
+    #[test]
+    fn vertical_presets_keep_the_front_up() {
+        let (top, up) = direction(CameraPreset::Top);
+        assert_close(top, [0.0, 1.0, 0.0]);
+        assert_close(up, [0.0, 0.0, -1.0]);
+        let (bottom, up) = direction(CameraPreset::Bottom);
+        assert_close(bottom, [0.0, -1.0, 0.0]);
+        assert_close(up, [0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn orbit_with_distance() {
+        let bounds = Bounds {
+            min: [0.0, 0.0, 0.0],
+            max: [2.0, 2.0, 2.0],
+        };
+        let orbit = CameraSpec::Orbit {
+            azimuth: 90.0,
+            elevation: 0.0,
+            distance: Some(5.0),
+        };
+        let pose = orbit.resolve(&bounds, &Framing::default(), 1.0, 0.8);
+        assert_close(pose.target, [1.0, 1.0, 1.0]);
+        assert_close(pose.eye, [6.0, 1.0, 1.0]);
+        assert_close(pose.up, [0.0, 1.0, 0.0]);
+    }
+}
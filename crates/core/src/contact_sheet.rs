@@ -0,0 +1,170 @@
+use image::{imageops, Rgba, RgbaImage};
+
+use crate::{
+    font::{draw_text, text_size},
+    resample::resample,
+    CameraPreset, CameraSpec,
+};
+
+/// One cell of a contact sheet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContactSheetView {
+    pub camera: CameraSpec,
+    pub label: Option<String>,
+}
+
+impl ContactSheetView {
+    pub fn new(camera: impl Into<CameraSpec>, label: Option<String>) -> Self {
+        Self {
+            camera: camera.into(),
+            label,
+        }
+    }
+}
+
+impl From<CameraPreset> for ContactSheetView {
+    fn from(preset: CameraPreset) -> Self {
+        Self::new(preset, Some(preset.name().to_owned()))
+    }
+}
+
+/// Layout of a grid of views rendered from the same asset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContactSheetOptions {
+    pub views: Vec<ContactSheetView>,
+    /// Number of columns, defaults to a grid that is as square as possible.
+    pub columns: Option<u32>,
+    /// Size of each cell, defaults to the size of the renderer. The cells are rendered at this
+    /// size, so the model is framed for their aspect ratio.
+    pub cell_size: Option<(u32, u32)>,
+    /// Space between cells in pixels.
+    pub gutter: u32,
+    /// Draw the label of each view in the bottom left corner of its cell.
+    pub labels: bool,
+}
+
+impl Default for ContactSheetOptions {
+    fn default() -> Self {
+        Self {
+            views: vec![
+                CameraPreset::Front.into(),
+                CameraPreset::Right.into(),
+                CameraPreset::Top.into(),
+                CameraPreset::Isometric.into(),
+            ],
+            columns: None,
+            cell_size: None,
+            gutter: 8,
+            labels: true,
+        }
+    }
+}
+
+impl ContactSheetOptions {
+    pub(crate) fn grid(&self) -> (u32, u32) {
+        let count = self.views.len() as u32;
+        if count == 0 {
+            return (0, 0);
+        }
+        let columns = self
+            .columns
+            .unwrap_or_else(|| (count as f32).sqrt().ceil() as u32)
+            .clamp(1, count);
+        (columns, count.div_ceil(columns))
+    }
+
+    /// Places the rendered `cells`, in the order of `views`, on a transparent sheet.
+    pub(crate) fn compose(&self, cells: Vec<RgbaImage>, cell_size: (u32, u32)) -> RgbaImage {
+        let (columns, rows) = self.grid();
+        let (cell_width, cell_height) = cell_size;
+        let mut sheet = RgbaImage::new(
+            (columns * cell_width + columns.saturating_sub(1) * self.gutter).max(1),
+            (rows * cell_height + rows.saturating_sub(1) * self.gutter).max(1),
+        );
+
+        for (index, (cell, view)) in cells.into_iter().zip(&self.views).enumerate() {
+            let cell = resample(&cell, cell_width, cell_height);
+            let x = (index as u32 % columns) * (cell_width + self.gutter);
+            let y = (index as u32 / columns) * (cell_height + self.gutter);
+            imageops::replace(&mut sheet, &cell, x as i64, y as i64);
+
+            if let (true, Some(label)) = (self.labels, &view.label) {
+                let scale = (cell_height / 160).max(1);
+                let padding = 2 * scale;
+                let (text_width, text_height) = text_size(label, scale);
+                let mut tag = RgbaImage::from_pixel(
+                    text_width + padding * 2,
+                    text_height + padding * 2,
+                    Rgba([0, 0, 0, 160]),
+                );
+                draw_text(
+                    &mut tag,
+                    label,
+                    padding as i64,
+                    padding as i64,
+                    scale,
+                    Rgba([255, 255, 255, 255]),
+                );
+                let margin = 4 * scale;
+                imageops::overlay(
+                    &mut sheet,
+                    &tag,
+                    (x + margin) as i64,
+                    (y + cell_height).saturating_sub(tag.height() + margin) as i64,
+                );
+            }
+        }
+
+        sheet
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use image::{Rgba, RgbaImage};
+
+    use crate::{CameraPreset, ContactSheetView};
+
+    use super::ContactSheetOptions;
+
+    fn options(count: usize, columns: Option<u32>) -> ContactSheetOptions {
+        ContactSheetOptions {
+            views: vec![ContactSheetView::new(CameraPreset::Front, None); count],
+            columns,
+            cell_size: None,
+            gutter: 1,
+            labels: false,
+        }
+    }
+
+    #[test]
+    fn grid_layout() {
+        assert_eq!(options(0, None).grid(), (0, 0));
+        assert_eq!(options(3, None).grid(), (2, 2));
+        assert_eq!(options(4, None).grid(), (2, 2));
+        assert_eq!(options(5, None).grid(), (3, 2));
+        assert_eq!(options(5, Some(2)).grid(), (2, 3));
+        assert_eq!(options(3, Some(10)).grid(), (3, 1));
+    }
+
+    #[test]
+    fn cells_are_placed_with_gutters() {
+        let color = |index: u8| Rgba([index * 80, 0, 0, 255]);
+        let cells = vec![
+            RgbaImage::from_pixel(4, 2, color(0)),
+            RgbaImage::from_pixel(4, 2, color(1)),
+            // resampled to the cell size
+            RgbaImage::from_pixel(8, 4, color(2)),
+        ];
+        let sheet = options(3, None).compose(cells, (4, 2));
+
+        assert_eq!(sheet.dimensions(), (9, 5));
+        assert_eq!(*sheet.get_pixel(0, 0), color(0));
+        assert_eq!(*sheet.get_pixel(8, 1), color(1));
+        assert_eq!(*sheet.get_pixel(3, 4), color(2));
+        // gutters and the missing fourth cell stay transparent
+        assert_eq!(sheet.get_pixel(4, 0).0[3], 0);
+        assert_eq!(sheet.get_pixel(0, 2).0[3], 0);
+        assert_eq!(sheet.get_pixel(5, 3).0[3], 0);
+    }
+}
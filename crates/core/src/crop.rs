@@ -0,0 +1,105 @@
+use image::{imageops, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use crate::resample::resample;
+
+/// Crops a render to its visible content and centers it again with an even padding.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AutoCrop {
+    /// Space kept on every side of the content, in percent of the shorter edge of the output.
+    pub padding: f32,
+    /// Pixels with an alpha at or below this value count as empty.
+    pub alpha_threshold: u8,
+}
+
+impl Default for AutoCrop {
+    fn default() -> Self {
+        Self {
+            padding: 5.0,
+            alpha_threshold: 0,
+        }
+    }
+}
+
+impl AutoCrop {
+    /// Crops the straight alpha `image` to its content and scales it into a transparent
+    /// `width` x `height` image. Images without any visible pixel are only resized.
+    pub fn apply(&self, image: &RgbaImage, width: u32, height: u32) -> RgbaImage {
+        let (x, y, content_width, content_height) = match self.content_bounds(image) {
+            Some(bounds) => bounds,
+            None => return resample(image, width, height),
+        };
+
+        let padding = (width.min(height) as f32 * self.padding.max(0.0) / 100.0).round() as u32;
+        let available_width = width.saturating_sub(padding * 2).max(1);
+        let available_height = height.saturating_sub(padding * 2).max(1);
+        let scale = f32::min(
+            available_width as f32 / content_width as f32,
+            available_height as f32 / content_height as f32,
+        );
+        let scaled_width = ((content_width as f32 * scale).round() as u32).clamp(1, width);
+        let scaled_height = ((content_height as f32 * scale).round() as u32).clamp(1, height);
+
+        let content = imageops::crop_imm(image, x, y, content_width, content_height).to_image();
+        let content = resample(&content, scaled_width, scaled_height);
+        let mut output = RgbaImage::new(width, height);
+        imageops::replace(
+            &mut output,
+            &content,
+            ((width - scaled_width) / 2) as i64,
+            ((height - scaled_height) / 2) as i64,
+        );
+        output
+    }
+
+    /// Returns `x`, `y`, width and height of the smallest rectangle holding every visible pixel.
+    fn content_bounds(&self, image: &RgbaImage) -> Option<(u32, u32, u32, u32)> {
+        let mut bounds: Option<(u32, u32, u32, u32)> = None;
+        for (x, y, pixel) in image.enumerate_pixels() {
+            if pixel[3] <= self.alpha_threshold {
+                continue;
+            }
+            bounds = Some(match bounds {
+                Some((min_x, min_y, max_x, max_y)) => {
+                    (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+                }
+                None => (x, y, x, y),
+            });
+        }
+        bounds.map(|(min_x, min_y, max_x, max_y)| {
+            (min_x, min_y, max_x - min_x + 1, max_y - min_y + 1)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use image::{Rgba, RgbaImage};
+
+    use super::AutoCrop;
+
+    #[test]
+    fn content_is_centered_with_padding() {
+        let mut image = RgbaImage::new(100, 100);
+        for y in 10..30 {
+            for x in 60..80 {
+                image.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+            }
+        }
+
+        let crop = AutoCrop {
+            padding: 10.0,
+            alpha_threshold: 0,
+        };
+        let output = crop.apply(&image, 100, 100);
+        assert_eq!(crop.content_bounds(&output), Some((10, 10, 80, 80)));
+    }
+
+    #[test]
+    fn empty_image_is_kept() {
+        let output = AutoCrop::default().apply(&RgbaImage::new(8, 8), 4, 4);
+        assert_eq!(output.dimensions(), (4, 4));
+        assert!(output.pixels().all(|pixel| pixel[3] == 0));
+    }
+}
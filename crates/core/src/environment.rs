@@ -0,0 +1,197 @@
+use std::{fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use filament_bindings::{
+    filament::{Engine, IndirectLight, IndirectLightBuilder, Skybox, SkyboxBuilder, Texture},
+    image::{ktx, KtxBundle},
+    math::{Float3, Float4, Mat3f},
+};
+
+use crate::{Result, SpaceThumbnailsError};
+
+const STUDIO_KTX_DATA: &[u8] = include_bytes!("lightroom_14b_ibl.ktx");
+
+/// Built-in lighting environments.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EnvironmentPreset {
+    /// The embedded studio light probe.
+    Studio,
+    /// Bright blue sky above a dark warm ground.
+    Outdoor,
+    /// Uniform grey light from every direction, no reflections.
+    NeutralGrey,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EnvironmentSource {
+    Preset(EnvironmentPreset),
+    /// A prefiltered cubemap in the KTX format produced by `cmgen`.
+    KtxFile(PathBuf),
+    KtxBytes(Vec<u8>),
+}
+
+/// Image based lighting of the scene.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Environment {
+    pub source: EnvironmentSource,
+    /// Illuminance in lux.
+    pub intensity: f32,
+    /// Rotation around the vertical axis in degrees.
+    pub rotation: f32,
+    /// Draw the environment behind the model. This covers the background.
+    pub skybox: bool,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self::preset(EnvironmentPreset::Studio)
+    }
+}
+
+impl Environment {
+    pub fn preset(preset: EnvironmentPreset) -> Self {
+        Self {
+            source: EnvironmentSource::Preset(preset),
+            intensity: match preset {
+                EnvironmentPreset::Studio => 50000.0,
+                EnvironmentPreset::Outdoor => 40000.0,
+                EnvironmentPreset::NeutralGrey => 30000.0,
+            },
+            rotation: -90.0,
+            skybox: false,
+        }
+    }
+
+    pub fn ktx_file(path: impl Into<PathBuf>) -> Self {
+        Self {
+            source: EnvironmentSource::KtxFile(path.into()),
+            ..Self::default()
+        }
+    }
+
+    pub fn ktx_bytes(data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            source: EnvironmentSource::KtxBytes(data.into()),
+            ..Self::default()
+        }
+    }
+}
+
+impl EnvironmentPreset {
+    /// Irradiance spherical harmonics (2 bands, linear RGB) of the presets that are not backed
+    /// by a cubemap, in the convention used by filament where
+    /// `irradiance(n) = sh[0] + sh[1] * n.y + sh[2] * n.z + sh[3] * n.x`.
+    pub(crate) fn irradiance(&self) -> Option<[[f32; 3]; 4]> {
+        match self {
+            EnvironmentPreset::Studio => None,
+            EnvironmentPreset::Outdoor => {
+                let sky = [0.60, 0.75, 1.0];
+                let ground = [0.35, 0.30, 0.25];
+                // radiance varies linearly with n.y, convolving the first band with the cosine
+                // lobe scales it by 2/3
+                let mut sh = [[0.0; 3]; 4];
+                for channel in 0..3 {
+                    sh[0][channel] = (sky[channel] + ground[channel]) / 2.0;
+                    sh[1][channel] = (sky[channel] - ground[channel]) / 2.0 * (2.0 / 3.0);
+                }
+                Some(sh)
+            }
+            EnvironmentPreset::NeutralGrey => Some([[0.5; 3], [0.0; 3], [0.0; 3], [0.0; 3]]),
+        }
+    }
+}
+
+/// Filament objects backing an `Environment`.
+pub(crate) struct EnvironmentResources {
+    texture: Option<Texture>,
+    pub ibl: IndirectLight,
+    pub skybox: Option<Skybox>,
+}
+
+impl EnvironmentResources {
+    pub unsafe fn create(engine: &mut Engine, environment: &Environment) -> Result<Self> {
+        let ktx_data = match &environment.source {
+            EnvironmentSource::Preset(EnvironmentPreset::Studio) => Some(STUDIO_KTX_DATA.to_vec()),
+            EnvironmentSource::Preset(_) => None,
+            EnvironmentSource::KtxFile(path) => {
+                Some(fs::read(path).map_err(|source| SpaceThumbnailsError::Io {
+                    path: path.to_owned(),
+                    source,
+                })?)
+            }
+            EnvironmentSource::KtxBytes(data) => Some(data.clone()),
+        };
+
+        let texture = match ktx_data {
+            Some(data) => Some(
+                KtxBundle::from(data.as_slice())
+                    .and_then(|bundle| ktx::create_texture(engine, bundle, false))
+                    .ok_or_else(|| SpaceThumbnailsError::CorruptAsset {
+                        format: "ktx".to_owned(),
+                        source: "not a valid ktx cubemap".into(),
+                    })?,
+            ),
+            None => None,
+        };
+
+        let mut builder = IndirectLightBuilder::new()
+            .ok_or(SpaceThumbnailsError::ResourceCreation("indirect light"))?
+            .intensity(environment.intensity)
+            .rotation(&Mat3f::rotation(
+                environment.rotation,
+                Float3::new(0.0, 1.0, 0.0),
+            ));
+        builder = match (&texture, &environment.source) {
+            (Some(texture), _) => builder.reflections(texture),
+            (None, EnvironmentSource::Preset(preset)) => {
+                let sh = preset.irradiance().unwrap_or_default();
+                builder.irradiance(2, &sh.map(Float3::from))
+            }
+            (None, _) => builder,
+        };
+        let ibl = builder
+            .build(engine)
+            .ok_or(SpaceThumbnailsError::ResourceCreation("indirect light"))?;
+
+        let skybox = if environment.skybox {
+            let builder = SkyboxBuilder::new()
+                .ok_or(SpaceThumbnailsError::ResourceCreation("skybox"))?
+                .intensity(environment.intensity);
+            let builder = match (&texture, &environment.source) {
+                (Some(texture), _) => builder.environment(texture),
+                (None, EnvironmentSource::Preset(preset)) => {
+                    let [r, g, b] = preset.irradiance().unwrap_or_default()[0];
+                    builder.color(&Float4::new(r, g, b, 1.0))
+                }
+                (None, _) => builder,
+            };
+            Some(
+                builder
+                    .build(engine)
+                    .ok_or(SpaceThumbnailsError::ResourceCreation("skybox"))?,
+            )
+        } else {
+            None
+        };
+
+        Ok(Self {
+            texture,
+            ibl,
+            skybox,
+        })
+    }
+
+    pub unsafe fn destroy(&mut self, engine: &mut Engine) {
+        if let Some(mut skybox) = self.skybox.take() {
+            engine.destroy_skybox(&mut skybox);
+        }
+        engine.destroy_indirect_light(&mut self.ibl);
+        if let Some(mut texture) = self.texture.take() {
+            engine.destroy_texture(&mut texture);
+        }
+    }
+}
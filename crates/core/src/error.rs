@@ -0,0 +1,103 @@
+use std::{error::Error, fmt, io, path::PathBuf};
+
+use crate::{AnimationSelector, EmbeddedCamera, RendererBackend, SceneSelector};
+
+pub type Result<T, E = SpaceThumbnailsError> = std::result::Result<T, E>;
+
+/// Everything that can go wrong while creating the renderer, loading an asset or reading back
+/// the rendered pixels.
+#[derive(Debug)]
+pub enum SpaceThumbnailsError {
+    /// The requested backend could not be initialized on this machine.
+    BackendUnavailable(RendererBackend),
+    /// Filament failed to create one of the renderer resources.
+    ResourceCreation(&'static str),
+    /// The model file could not be read.
+    Io { path: PathBuf, source: io::Error },
+    /// An image referenced by the render options could not be loaded.
+    ImageLoad {
+        path: PathBuf,
+        source: image::ImageError,
+    },
+    /// The file name has no usable extension or is not valid unicode.
+    InvalidFilename(PathBuf),
+    /// No loader is able to handle this kind of file.
+    UnsupportedFormat(String),
+    /// The loader recognized the format but could not parse the content.
+    CorruptAsset {
+        format: String,
+        source: Box<dyn Error + Send + Sync>,
+    },
+    /// The asset references an external resource that could not be resolved.
+    MissingExternalResource(String),
+    /// The loaded asset has no animation matching the requested pose.
+    AnimationNotFound(AnimationSelector),
+    /// The glTF asset has no scene matching the selected one.
+    SceneNotFound(SceneSelector),
+    /// The glTF asset has no `KHR_materials_variants` variant with this name.
+    MaterialVariantNotFound(String),
+    /// The model file has no camera matching the selected embedded camera.
+    CameraNotFound(EmbeddedCamera),
+    /// The ZIP archive has no entry with this path.
+    MissingArchiveEntry(String),
+    /// No entry of the ZIP archive is a supported model.
+    NoModelInArchive,
+    /// The files of a model loaded through a `Vfs` exceed what is copied to the disk.
+    ResourceLimit { files: usize, bytes: u64 },
+    /// The buffer passed to `take_screenshot_sync` is smaller than the screenshot.
+    BufferTooSmall { required: usize, provided: usize },
+    /// The renderer did not deliver the pixels of the rendered frame.
+    ReadbackFailed,
+    /// The rendered frames could not be encoded.
+    Encode(Box<dyn Error + Send + Sync>),
+}
+
+impl fmt::Display for SpaceThumbnailsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BackendUnavailable(backend) => {
+                write!(f, "renderer backend {:?} is not available", backend)
+            }
+            Self::ResourceCreation(resource) => write!(f, "failed to create {}", resource),
+            Self::Io { path, .. } => write!(f, "failed to read {}", path.display()),
+            Self::ImageLoad { path, .. } => write!(f, "failed to load image {}", path.display()),
+            Self::InvalidFilename(path) => write!(f, "invalid file name {}", path.display()),
+            Self::UnsupportedFormat(format) => write!(f, "unsupported format \"{}\"", format),
+            Self::CorruptAsset { format, .. } => write!(f, "failed to parse {} asset", format),
+            Self::MissingExternalResource(uri) => {
+                write!(f, "external resource \"{}\" could not be resolved", uri)
+            }
+            Self::AnimationNotFound(animation) => write!(f, "animation {} not found", animation),
+            Self::SceneNotFound(scene) => write!(f, "scene {} not found", scene),
+            Self::MaterialVariantNotFound(variant) => {
+                write!(f, "material variant \"{}\" not found", variant)
+            }
+            Self::CameraNotFound(camera) => write!(f, "camera {} not found", camera),
+            Self::MissingArchiveEntry(entry) => write!(f, "archive has no entry \"{}\"", entry),
+            Self::NoModelInArchive => write!(f, "archive does not contain a supported model"),
+            Self::ResourceLimit { files, bytes } => write!(
+                f,
+                "model files exceed the limit of {} files or {} bytes",
+                files, bytes
+            ),
+            Self::BufferTooSmall { required, provided } => write!(
+                f,
+                "output buffer is too small, {} bytes required but {} provided",
+                required, provided
+            ),
+            Self::ReadbackFailed => write!(f, "failed to read back the rendered pixels"),
+            Self::Encode(_) => write!(f, "failed to encode the rendered frames"),
+        }
+    }
+}
+
+impl Error for SpaceThumbnailsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::ImageLoad { source, .. } => Some(source),
+            Self::CorruptAsset { source, .. } | Self::Encode(source) => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
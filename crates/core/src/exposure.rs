@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+
+/// Camera exposure.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Exposure {
+    /// Aperture in f-stops, shutter speed in seconds and sensitivity in ISO.
+    Physical {
+        aperture: f32,
+        shutter_speed: f32,
+        sensitivity: f32,
+    },
+    /// Exposure value at ISO 100.
+    Ev100(f32),
+    /// Renders the asset once and picks the exposure that brings its average luminance to
+    /// middle grey. `compensation` is added to the metered exposure, in stops.
+    Auto {
+        #[serde(default)]
+        compensation: f32,
+    },
+}
+
+impl Default for Exposure {
+    fn default() -> Self {
+        Self::Physical {
+            aperture: 16.0,
+            shutter_speed: 1.0 / 125.0,
+            sensitivity: 100.0,
+        }
+    }
+}
+
+impl Exposure {
+    /// Exposure value at ISO 100, `None` for automatic exposure.
+    pub fn ev100(&self) -> Option<f32> {
+        match *self {
+            Exposure::Physical {
+                aperture,
+                shutter_speed,
+                sensitivity,
+            } => Some((aperture * aperture / shutter_speed * 100.0 / sensitivity).log2()),
+            Exposure::Ev100(ev100) => Some(ev100),
+            Exposure::Auto { .. } => None,
+        }
+    }
+}
+
+/// Tone mapping operator of the view.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ToneMapping {
+    Aces,
+    /// The default of filament, ACES with a brighter midpoint.
+    #[default]
+    AcesLegacy,
+    Filmic,
+    Linear,
+}
+
+/// Linear luminance that automatic exposure aims for.
+const MIDDLE_GREY: f32 = 0.18;
+
+/// Exposure value that brings the `luminance` of a render at `ev100` to middle grey, shifted by
+/// `compensation` stops. A step moves at most 4 stops, the luminance of a clipped render says
+/// little about how far off it is.
+pub(crate) fn metered_ev100(ev100: f32, luminance: f32, compensation: f32) -> f32 {
+    let target = MIDDLE_GREY * compensation.exp2();
+    ev100 + (luminance / target).log2().clamp(-4.0, 4.0)
+}
+
+/// Log-average linear luminance of the covered pixels of a straight alpha sRGB image, `None`
+/// if nothing is covered.
+pub(crate) fn average_luminance(pixels: &[u8]) -> Option<f32> {
+    let to_linear = |value: u8| {
+        let value = value as f32 / 255.0;
+        if value <= 0.04045 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    };
+
+    let (sum, count) = pixels
+        .chunks_exact(4)
+        .filter(|pixel| pixel[3] > 0)
+        .map(|pixel| {
+            0.2126 * to_linear(pixel[0])
+                + 0.7152 * to_linear(pixel[1])
+                + 0.0722 * to_linear(pixel[2])
+        })
+        .fold((0.0f64, 0usize), |(sum, count), luminance| {
+            (sum + (luminance.max(1e-4) as f64).ln(), count + 1)
+        });
+
+    if count == 0 {
+        None
+    } else {
+        Some((sum / count as f64).exp() as f32)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        exposure::{average_luminance, metered_ev100, MIDDLE_GREY},
+        Exposure,
+    };
+
+    #[test]
+    fn physical_and_ev100_agree() {
+        let ev100 = Exposure::default().ev100().unwrap();
+        assert!((ev100 - 14.966).abs() < 1e-3);
+    }
+
+    #[test]
+    fn luminance_ignores_transparent_pixels() {
+        let pixels = [255, 255, 255, 255, 0, 0, 0, 0, 255, 255, 255, 255];
+        assert!((average_luminance(&pixels).unwrap() - 1.0).abs() < 1e-4);
+        assert_eq!(average_luminance(&[0, 0, 0, 0]), None);
+    }
+
+    #[test]
+    fn luminance_is_a_log_average() {
+        let grey = average_luminance(&[118, 118, 118, 255]).unwrap();
+        assert!((grey - MIDDLE_GREY).abs() < 1e-2);
+        let mixed = average_luminance(&[118, 118, 118, 255, 255, 255, 255, 255]).unwrap();
+        assert!((mixed - grey.sqrt()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn metering_steps() {
+        assert!((metered_ev100(10.0, MIDDLE_GREY, 0.0) - 10.0).abs() < 1e-4);
+        // twice too bright needs one more stop of exposure value
+        assert!((metered_ev100(10.0, MIDDLE_GREY * 2.0, 0.0) - 11.0).abs() < 1e-4);
+        assert!((metered_ev100(10.0, MIDDLE_GREY, 1.0) - 9.0).abs() < 1e-4);
+        assert!((metered_ev100(10.0, 1.0e3, 0.0) - 14.0).abs() < 1e-4);
+        assert!((metered_ev100(10.0, 1.0e-4, 0.0) - 6.0).abs() < 1e-4);
+    }
+}
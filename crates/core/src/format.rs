@@ -0,0 +1,296 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Bytes of the beginning of a file inspected to recognize its format.
+const HEAD_SIZE: usize = 4096;
+
+/// Bytes of the beginning of a JSON file searched for the `asset` of a glTF document, the
+/// arrays before it may be long but a document is never parsed whole.
+const JSON_PREFIX_SIZE: usize = 1 << 20;
+
+/// The formats recognized from the content of a model file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelFormat {
+    Gltf,
+    Glb,
+    /// Binary and ASCII FBX.
+    Fbx,
+    Obj,
+    /// Binary and ASCII STL.
+    Stl,
+    Ply,
+    #[serde(rename = "dae")]
+    Collada,
+    #[serde(rename = "3ds")]
+    ThreeDs,
+    Blend,
+    Off,
+    #[serde(rename = "x")]
+    DirectX,
+}
+
+impl ModelFormat {
+    /// Recognizes the format of a model from its content, `None` when it is unknown.
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        match Self::detect_head(data, data.len() as u64) {
+            None if is_gltf_json(data) => Some(Self::Gltf),
+            format => format,
+        }
+    }
+
+    /// Recognizes the format of a model file, reading only its beginning, a longer one when it
+    /// may be a glTF document whose `asset` comes late.
+    pub(crate) fn detect_file(path: &Path) -> io::Result<Option<Self>> {
+        let mut file = File::open(path)?;
+        let len = file.metadata()?.len();
+        let mut head = Vec::with_capacity(HEAD_SIZE);
+        (&mut file).take(HEAD_SIZE as u64).read_to_end(&mut head)?;
+        match Self::detect_head(&head, len) {
+            None if matches!(text(&head), Some(text) if text.starts_with('{')) => {
+                (&mut file)
+                    .take((JSON_PREFIX_SIZE - head.len()) as u64)
+                    .read_to_end(&mut head)?;
+                Ok(Self::detect(&head))
+            }
+            format => Ok(format),
+        }
+    }
+
+    /// Recognizes the format from the first bytes of a file of `len` bytes.
+    fn detect_head(head: &[u8], len: u64) -> Option<Self> {
+        let head = &head[..head.len().min(HEAD_SIZE)];
+        if head.starts_with(b"glTF") {
+            return Some(Self::Glb);
+        }
+        if head.starts_with(b"Kaydara FBX Binary") {
+            return Some(Self::Fbx);
+        }
+        if head.starts_with(b"BLENDER") {
+            return Some(Self::Blend);
+        }
+        if head.starts_with(b"xof ") {
+            return Some(Self::DirectX);
+        }
+        // binary STL headers may start with "solid" too, the size is checked first
+        if is_binary_stl(head, len) {
+            return Some(Self::Stl);
+        }
+        if is_3ds(head, len) {
+            return Some(Self::ThreeDs);
+        }
+
+        let text = text(head)?;
+        let first_line = text.lines().next().unwrap_or_default().trim();
+        if first_line == "ply" {
+            Some(Self::Ply)
+        } else if is_gltf_json(head) {
+            Some(Self::Gltf)
+        } else if first_line.starts_with("; FBX") {
+            Some(Self::Fbx)
+        } else if first_line.starts_with("solid") && text.contains("facet") {
+            Some(Self::Stl)
+        } else if text.starts_with('<') && text.contains("<COLLADA") {
+            Some(Self::Collada)
+        } else if first_line.starts_with("OFF") {
+            Some(Self::Off)
+        } else if is_obj(text) {
+            Some(Self::Obj)
+        } else {
+            None
+        }
+    }
+
+    /// The lowercase file extension of the format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Gltf => "gltf",
+            Self::Glb => "glb",
+            Self::Fbx => "fbx",
+            Self::Obj => "obj",
+            Self::Stl => "stl",
+            Self::Ply => "ply",
+            Self::Collada => "dae",
+            Self::ThreeDs => "3ds",
+            Self::Blend => "blend",
+            Self::Off => "off",
+            Self::DirectX => "x",
+        }
+    }
+}
+
+impl fmt::Display for ModelFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+/// The beginning of a text file without its byte order mark and leading whitespace, `None` for
+/// binary content.
+fn text(head: &[u8]) -> Option<&str> {
+    let head = head.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(head);
+    if head.contains(&0) {
+        return None;
+    }
+    // the head may end in the middle of a character
+    let text = match std::str::from_utf8(head) {
+        Ok(text) => text,
+        Err(err) if err.error_len().is_none() => {
+            std::str::from_utf8(&head[..err.valid_up_to()]).ok()?
+        }
+        Err(_) => return None,
+    };
+    Some(text.trim_start())
+}
+
+/// A JSON object with an `asset` object holding a `version`, searched in the first
+/// `JSON_PREFIX_SIZE` bytes without parsing them.
+fn is_gltf_json(data: &[u8]) -> bool {
+    let text = match text(&data[..data.len().min(JSON_PREFIX_SIZE)]) {
+        Some(text) if text.starts_with('{') => text,
+        _ => return false,
+    };
+    text.match_indices("\"asset\"").any(|(index, key)| {
+        let value = text[index + key.len()..].trim_start();
+        matches!(
+            value.strip_prefix(':').map(str::trim_start),
+            Some(value) if value.starts_with('{') && value.contains("\"version\"")
+        )
+    })
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// An 80 bytes header and a triangle count, followed by 50 bytes per triangle.
+fn is_binary_stl(head: &[u8], len: u64) -> bool {
+    match read_u32(head, 80) {
+        Some(count) => len == 84 + u64::from(count) * 50,
+        None => false,
+    }
+}
+
+/// The main chunk, `0x4D4D`, spans the whole file and starts with the version chunk `0x0002` or
+/// the editor chunk `0x3D3D`.
+fn is_3ds(head: &[u8], len: u64) -> bool {
+    let chunk_id = |offset: usize| head.get(offset..offset + 2).map(|id| [id[0], id[1]]);
+    chunk_id(0) == Some([0x4D, 0x4D])
+        && (read_u32(head, 2).map(u64::from) == Some(len)
+            || matches!(chunk_id(6), Some([0x02, 0x00] | [0x3D, 0x3D])))
+}
+
+/// Every statement is an OBJ one and at least one vertex is defined.
+fn is_obj(text: &str) -> bool {
+    let mut lines = text.lines().collect::<Vec<_>>();
+    // the last line may be cut by the end of the head
+    if lines.len() > 1 {
+        lines.pop();
+    }
+    let mut vertices = false;
+    for line in lines {
+        let keyword = match line.split_whitespace().next() {
+            Some(keyword) if !keyword.starts_with('#') => keyword,
+            _ => continue,
+        };
+        match keyword {
+            "v" => vertices = true,
+            "vt" | "vn" | "vp" | "f" | "l" | "p" | "o" | "g" | "s" | "mtllib" | "usemtl" => {}
+            _ => return false,
+        }
+    }
+    vertices
+}
+
+#[cfg(test)]
+mod test {
+    use super::ModelFormat;
+
+    fn binary_stl(triangles: u32, header: &[u8]) -> Vec<u8> {
+        let mut data = header.to_vec();
+        data.resize(80, 0);
+        data.extend_from_slice(&triangles.to_le_bytes());
+        data.resize(84 + triangles as usize * 50, 0);
+        data
+    }
+
+    #[test]
+    fn binary_formats() {
+        assert_eq!(
+            ModelFormat::detect(b"glTF\x02\x00\x00\x00"),
+            Some(ModelFormat::Glb)
+        );
+        assert_eq!(
+            ModelFormat::detect(b"Kaydara FBX Binary  \x00\x1a\x00"),
+            Some(ModelFormat::Fbx)
+        );
+        assert_eq!(
+            ModelFormat::detect(&binary_stl(2, b"solid exported")),
+            Some(ModelFormat::Stl)
+        );
+        let mut three_ds = vec![0x4D, 0x4D, 16, 0, 0, 0, 0x02, 0x00, 10, 0, 0, 0];
+        three_ds.extend_from_slice(&[3, 0, 0, 0]);
+        assert_eq!(ModelFormat::detect(&three_ds), Some(ModelFormat::ThreeDs));
+        assert_eq!(ModelFormat::detect(&[0x4D, 0x4D, 1, 2, 3]), None);
+    }
+
+    #[test]
+    fn text_formats() {
+        assert_eq!(
+            ModelFormat::detect(b"ply\nformat ascii 1.0\nend_header\n"),
+            Some(ModelFormat::Ply)
+        );
+        assert_eq!(
+            ModelFormat::detect(b"solid cube\n  facet normal 0 0 1\n"),
+            Some(ModelFormat::Stl)
+        );
+        assert_eq!(
+            ModelFormat::detect(b"; FBX 7.3.0 project file\n"),
+            Some(ModelFormat::Fbx)
+        );
+        assert_eq!(
+            ModelFormat::detect(b"<?xml version=\"1.0\"?>\n<COLLADA version=\"1.4.1\">"),
+            Some(ModelFormat::Collada)
+        );
+        assert_eq!(
+            ModelFormat::detect(b"# cube\nmtllib cube.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n"),
+            Some(ModelFormat::Obj)
+        );
+        assert_eq!(ModelFormat::detect(b"hello world\nv 0 0 0\n"), None);
+    }
+
+    #[test]
+    fn gltf_json() {
+        assert_eq!(
+            ModelFormat::detect(b"\xEF\xBB\xBF{ \"asset\": { \"version\": \"2.0\" } }"),
+            Some(ModelFormat::Gltf)
+        );
+        // `asset` after a long array is past the head
+        let late = format!(
+            "{{\"accessors\": [{}0], \"asset\": {{\"version\": \"2.0\"}}}}",
+            "0,".repeat(4096)
+        );
+        assert_eq!(
+            ModelFormat::detect(late.as_bytes()),
+            Some(ModelFormat::Gltf)
+        );
+        assert_eq!(ModelFormat::detect(b"{\"name\": \"not a model\"}"), None);
+        assert_eq!(
+            ModelFormat::detect(b"{\"name\": \"asset\", \"version\": 2}"),
+            None
+        );
+        // the document is not searched past its prefix
+        let too_late = format!(
+            "{{\"accessors\": [{}0], \"asset\": {{\"version\": \"2.0\"}}}}",
+            "0,".repeat(super::JSON_PREFIX_SIZE / 2)
+        );
+        assert_eq!(ModelFormat::detect(too_late.as_bytes()), None);
+    }
+}
@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+
+use crate::bounds::{cross, dot, length, normalize, sub, Bounds};
+
+/// Which volume of the model has to fit into the viewport.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FramingMode {
+    /// Fit the bounding sphere, the distance does not depend on the viewpoint.
+    BoundingSphere,
+    /// Fit the projected corners of the bounding box, the tightest fit.
+    BoundingBox,
+}
+
+/// Controls how far the camera is moved back from the model.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Framing {
+    pub mode: FramingMode,
+    /// Space kept around the model, in percent of the viewport.
+    pub margin: f32,
+}
+
+impl Default for Framing {
+    fn default() -> Self {
+        Self {
+            mode: FramingMode::BoundingBox,
+            margin: 5.0,
+        }
+    }
+}
+
+/// Orthonormal camera basis, `backward` points from the target towards the eye.
+pub(crate) struct ViewBasis {
+    pub right: [f32; 3],
+    pub up: [f32; 3],
+    pub backward: [f32; 3],
+}
+
+impl ViewBasis {
+    pub fn new(backward: [f32; 3], up: [f32; 3]) -> Self {
+        let backward = normalize(backward);
+        let right = normalize(cross(up, backward));
+        let up = cross(backward, right);
+        Self {
+            right,
+            up,
+            backward,
+        }
+    }
+}
+
+impl Framing {
+    fn scale(&self) -> f32 {
+        1.0 + self.margin.max(0.0) / 100.0
+    }
+
+    /// Distance from the center of `bounds` at which a perspective camera looking along
+    /// `-basis.backward` shows the whole model. `vertical_fov` is in radians.
+    pub(crate) fn perspective_distance(
+        &self,
+        bounds: &Bounds,
+        basis: &ViewBasis,
+        aspect: f32,
+        vertical_fov: f32,
+    ) -> f32 {
+        let tan_y = (vertical_fov / 2.0).tan() / self.scale();
+        let tan_x = tan_y * aspect;
+
+        match self.mode {
+            FramingMode::BoundingSphere => {
+                let radius = length(bounds.half_extent());
+                let half_fov = tan_x.min(tan_y).atan();
+                radius / half_fov.sin()
+            }
+            FramingMode::BoundingBox => {
+                let center = bounds.center();
+                bounds
+                    .corners()
+                    .iter()
+                    .map(|corner| {
+                        let offset = sub(*corner, center);
+                        let x = dot(offset, basis.right).abs();
+                        let y = dot(offset, basis.up).abs();
+                        let z = dot(offset, basis.backward);
+                        z + (x / tan_x).max(y / tan_y)
+                    })
+                    .fold(0.0, f32::max)
+            }
+        }
+    }
+
+    /// Half width and half height of an orthographic frustum looking along `-basis.backward`
+    /// that shows the whole model around `center`.
+    pub(crate) fn orthographic_extent(
+        &self,
+        bounds: &Bounds,
+        center: [f32; 3],
+        basis: &ViewBasis,
+        aspect: f32,
+    ) -> (f32, f32) {
+        let (half_width, half_height) = match self.mode {
+            FramingMode::BoundingSphere => {
+                let radius = length(sub(bounds.center(), center)) + length(bounds.half_extent());
+                (radius, radius)
+            }
+            FramingMode::BoundingBox => bounds
+                .corners()
+                .iter()
+                .map(|corner| {
+                    let offset = sub(*corner, center);
+                    (dot(offset, basis.right).abs(), dot(offset, basis.up).abs())
+                })
+                .fold((0.0f32, 0.0f32), |(w, h), (x, y)| (w.max(x), h.max(y))),
+        };
+        let half_height = half_height.max(half_width / aspect) * self.scale();
+        (half_height * aspect, half_height)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{bounds::Bounds, framing::ViewBasis, Framing, FramingMode};
+
+    const CUBE: Bounds = Bounds {
+        min: [-1.0, -1.0, -1.0],
+        max: [1.0, 1.0, 1.0],
+    };
+
+    #[test]
+    fn front_view_of_cube_fits_exactly() {
+        let framing = Framing {
+            mode: FramingMode::BoundingBox,
+            margin: 0.0,
+        };
+        let basis = ViewBasis::new([0.0, 0.0, 1.0], [0.0, 1.0, 0.0]);
+        let distance = framing.perspective_distance(&CUBE, &basis, 1.0, 90f32.to_radians());
+        // the front face is at z = 1 and must span the 90 degree frustum
+        assert!((distance - 2.0).abs() < 1e-4);
+
+        let wide = framing.perspective_distance(&CUBE, &basis, 2.0, 90f32.to_radians());
+        assert!((wide - distance).abs() < 1e-4);
+    }
+
+    #[test]
+    fn margin_and_sphere_move_camera_back() {
+        let basis = ViewBasis::new([1.0, 1.0, 1.0], [0.0, 1.0, 0.0]);
+        let fov = 40f32.to_radians();
+        let tight = Framing {
+            mode: FramingMode::BoundingBox,
+            margin: 0.0,
+        };
+        let padded = Framing {
+            margin: 20.0,
+            ..tight
+        };
+        let sphere = Framing {
+            mode: FramingMode::BoundingSphere,
+            ..tight
+        };
+        let tight_distance = tight.perspective_distance(&CUBE, &basis, 1.0, fov);
+        assert!(padded.perspective_distance(&CUBE, &basis, 1.0, fov) > tight_distance);
+        assert!(sphere.perspective_distance(&CUBE, &basis, 1.0, fov) >= tight_distance);
+    }
+
+    #[test]
+    fn orthographic_extent_follows_aspect() {
+        let framing = Framing {
+            mode: FramingMode::BoundingBox,
+            margin: 0.0,
+        };
+        let basis = ViewBasis::new([0.0, 0.0, 1.0], [0.0, 1.0, 0.0]);
+        let flat = Bounds {
+            min: [-2.0, -0.5, -1.0],
+            max: [2.0, 0.5, 1.0],
+        };
+        assert_eq!(
+            framing.orthographic_extent(&flat, flat.center(), &basis, 1.0),
+            (2.0, 2.0)
+        );
+        assert_eq!(
+            framing.orthographic_extent(&flat, flat.center(), &basis, 4.0),
+            (2.0, 0.5)
+        );
+    }
+}
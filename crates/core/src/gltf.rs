@@ -0,0 +1,430 @@
+use std::collections::HashSet;
+
+use serde::Deserialize;
+
+use crate::{
+    bounds::{normalize, scale},
+    Bounds, ModelInfo,
+};
+
+const GLB_MAGIC: &[u8] = b"glTF";
+const GLB_JSON_CHUNK: u32 = 0x4e4f_534a;
+
+/// Column-major 4x4 matrix.
+type Matrix = [f32; 16];
+
+const IDENTITY: Matrix = [
+    1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+];
+
+/// The parts of a glTF document gltfio does not expose or that are needed before the asset is
+/// created, read without loading any buffer.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Document {
+    pub extensions_used: Vec<String>,
+    pub accessors: Vec<Accessor>,
+    pub meshes: Vec<Mesh>,
+    pub textures: Vec<serde_json::Value>,
+    pub nodes: Vec<Node>,
+    pub cameras: Vec<Camera>,
+    pub animations: Vec<Named>,
+    pub scene: Option<usize>,
+    pub scenes: Vec<Scene>,
+    pub extensions: Extensions,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct Accessor {
+    pub count: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct Mesh {
+    pub primitives: Vec<Primitive>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct Primitive {
+    pub attributes: Attributes,
+    pub indices: Option<usize>,
+    pub mode: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct Attributes {
+    #[serde(rename = "POSITION")]
+    pub position: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct Named {
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct Scene {
+    pub name: Option<String>,
+    pub nodes: Vec<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct Node {
+    pub name: Option<String>,
+    pub camera: Option<usize>,
+    pub children: Vec<usize>,
+    pub matrix: Option<Matrix>,
+    pub translation: Option<[f32; 3]>,
+    pub rotation: Option<[f32; 4]>,
+    pub scale: Option<[f32; 3]>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct Camera {
+    pub name: Option<String>,
+    pub perspective: Option<Perspective>,
+    pub orthographic: Option<Orthographic>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct Perspective {
+    pub yfov: f32,
+    pub znear: f32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct Orthographic {
+    pub ymag: f32,
+    pub znear: f32,
+}
+
+/// Items that may have a name.
+pub(crate) trait Name {
+    fn name(&self) -> Option<&str>;
+}
+
+macro_rules! impl_name {
+    ($($item:ty),*) => {
+        $(impl Name for $item {
+            fn name(&self) -> Option<&str> {
+                self.name.as_deref()
+            }
+        })*
+    };
+}
+
+impl_name!(Named, Scene, Node, Camera);
+
+/// Names reported by the loaders, empty when the item is unnamed.
+impl Name for String {
+    fn name(&self) -> Option<&str> {
+        Some(self.as_str()).filter(|name| !name.is_empty())
+    }
+}
+
+impl Name for Option<String> {
+    fn name(&self) -> Option<&str> {
+        self.as_deref().filter(|name| !name.is_empty())
+    }
+}
+
+/// A node of the scene holding a camera, in the units of the file.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct CameraNode {
+    /// Index of the camera in the document.
+    pub camera: usize,
+    pub eye: [f32; 3],
+    pub forward: [f32; 3],
+    pub up: [f32; 3],
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct Extensions {
+    #[serde(rename = "KHR_materials_variants")]
+    pub materials_variants: Option<MaterialsVariants>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct MaterialsVariants {
+    pub variants: Vec<Named>,
+}
+
+impl Document {
+    /// Parses a `.gltf` JSON document or the JSON chunk of a `.glb` container.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let json = if data.starts_with(GLB_MAGIC) {
+            let word = |offset: usize| {
+                data.get(offset..offset + 4)
+                    .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            };
+            let length = word(12)? as usize;
+            if word(16)? != GLB_JSON_CHUNK {
+                return None;
+            }
+            data.get(20..20 + length)?
+        } else {
+            data
+        };
+        serde_json::from_slice(json).ok()
+    }
+
+    /// Names of the `KHR_materials_variants` variants, in the order of their indices.
+    pub fn material_variants(&self) -> Vec<String> {
+        self.extensions
+            .materials_variants
+            .as_ref()
+            .map(|variants| names(&variants.variants, "variant"))
+            .unwrap_or_default()
+    }
+
+    /// Cameras placed in `scene`, or in the default scene when `None`, in the order of the
+    /// node hierarchy.
+    pub fn camera_nodes(&self, scene: Option<usize>) -> Vec<CameraNode> {
+        let roots = match self.scenes.get(scene.or(self.scene).unwrap_or(0)) {
+            Some(scene) => scene.nodes.clone(),
+            // without scenes every node that is not a child of another one is a root
+            None => {
+                let children = self
+                    .nodes
+                    .iter()
+                    .flat_map(|node| node.children.iter().copied())
+                    .collect::<HashSet<_>>();
+                (0..self.nodes.len())
+                    .filter(|index| !children.contains(index))
+                    .collect()
+            }
+        };
+        let mut cameras = Vec::new();
+        for root in roots {
+            self.collect_cameras(root, &IDENTITY, 0, &mut cameras);
+        }
+        cameras
+    }
+
+    fn collect_cameras(
+        &self,
+        index: usize,
+        parent: &Matrix,
+        depth: usize,
+        cameras: &mut Vec<CameraNode>,
+    ) {
+        // a valid hierarchy is never deeper than the node count, stop on cycles
+        let node = match self.nodes.get(index) {
+            Some(node) if depth <= self.nodes.len() => node,
+            _ => return,
+        };
+        let world = multiply(parent, &node.local_matrix());
+        if let Some(camera) = node.camera {
+            cameras.push(CameraNode {
+                camera,
+                eye: [world[12], world[13], world[14]],
+                forward: normalize(scale([world[8], world[9], world[10]], -1.0)),
+                up: normalize([world[4], world[5], world[6]]),
+            });
+        }
+        for child in &node.children {
+            self.collect_cameras(*child, &world, depth + 1, cameras);
+        }
+    }
+
+    /// The statistics gltfio does not report: the geometry and texture counts, the extensions and
+    /// the names of the cameras, scenes and variants as the selectors resolve them.
+    pub fn model_info(&self, format: &str, bounds: Bounds) -> ModelInfo {
+        let accessor_count = |index: Option<usize>| {
+            index
+                .and_then(|index| self.accessors.get(index))
+                .map(|accessor| accessor.count)
+        };
+        let primitives = self.meshes.iter().flat_map(|mesh| &mesh.primitives);
+        let vertex_count = primitives
+            .clone()
+            .filter_map(|primitive| accessor_count(primitive.attributes.position))
+            .sum();
+        let triangle_count = primitives
+            .map(|primitive| {
+                let count = accessor_count(primitive.indices)
+                    .or_else(|| accessor_count(primitive.attributes.position))
+                    .unwrap_or(0);
+                match primitive.mode.unwrap_or(4) {
+                    4 => count / 3,
+                    5 | 6 => count.saturating_sub(2),
+                    _ => 0,
+                }
+            })
+            .sum();
+
+        ModelInfo {
+            triangle_count: Some(triangle_count),
+            vertex_count: Some(vertex_count),
+            texture_count: Some(self.textures.len() as u32),
+            cameras: Some(names(&self.cameras, "camera")),
+            scenes: Some(names(&self.scenes, "scene")),
+            material_variants: Some(self.material_variants()),
+            extensions_used: self.extensions_used.clone(),
+            ..ModelInfo::unknown(format, bounds)
+        }
+    }
+}
+
+impl Node {
+    fn local_matrix(&self) -> Matrix {
+        if let Some(matrix) = self.matrix {
+            return matrix;
+        }
+        let [x, y, z, w] = self.rotation.unwrap_or([0.0, 0.0, 0.0, 1.0]);
+        let [sx, sy, sz] = self.scale.unwrap_or([1.0; 3]);
+        let [tx, ty, tz] = self.translation.unwrap_or([0.0; 3]);
+        [
+            (1.0 - 2.0 * (y * y + z * z)) * sx,
+            2.0 * (x * y + z * w) * sx,
+            2.0 * (x * z - y * w) * sx,
+            0.0,
+            2.0 * (x * y - z * w) * sy,
+            (1.0 - 2.0 * (x * x + z * z)) * sy,
+            2.0 * (y * z + x * w) * sy,
+            0.0,
+            2.0 * (x * z + y * w) * sz,
+            2.0 * (y * z - x * w) * sz,
+            (1.0 - 2.0 * (x * x + y * y)) * sz,
+            0.0,
+            tx,
+            ty,
+            tz,
+            1.0,
+        ]
+    }
+}
+
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut result = [0.0; 16];
+    for column in 0..4 {
+        for row in 0..4 {
+            result[column * 4 + row] = (0..4).map(|k| a[k * 4 + row] * b[column * 4 + k]).sum();
+        }
+    }
+    result
+}
+
+/// Names of `items`, unnamed items are called `"{fallback} {index}"`.
+pub(crate) fn names(items: &[impl Name], fallback: &str) -> Vec<String> {
+    items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| match item.name() {
+            Some(name) => name.to_owned(),
+            None => format!("{} {}", fallback, index),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Bounds, SceneSelector};
+
+    use super::{names, Document};
+
+    const TRIANGLES: &str = r#"{
+        "asset": { "version": "2.0" },
+        "extensionsUsed": ["KHR_materials_variants"],
+        "accessors": [{ "count": 4 }, { "count": 6 }],
+        "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 }, "indices": 1 }] }],
+        "materials": [{ "name": "red" }, {}],
+        "nodes": [{ "mesh": 0 }, { "camera": 0 }],
+        "cameras": [{ "type": "perspective" }],
+        "animations": [{ "name": "spin", "channels": [], "samplers": [] }],
+        "scenes": [{ "nodes": [0] }, { "name": "camera", "nodes": [1] }],
+        "extensions": {
+            "KHR_materials_variants": { "variants": [{ "name": "red" }, { "name": "blue" }] }
+        }
+    }"#;
+
+    #[test]
+    fn gltf_statistics() {
+        let bounds = Bounds {
+            min: [0.0; 3],
+            max: [1.0; 3],
+        };
+        let info = Document::parse(TRIANGLES.as_bytes())
+            .unwrap()
+            .model_info("gltf", bounds);
+        assert_eq!(info.vertex_count, Some(4));
+        assert_eq!(info.triangle_count, Some(2));
+        assert_eq!(info.texture_count, Some(0));
+        assert_eq!(info.cameras, Some(vec!["camera 0".to_owned()]));
+        assert_eq!(info.extensions_used, vec!["KHR_materials_variants"]);
+        // counted on the loaded asset
+        assert_eq!(info.material_count, None);
+        assert_eq!(info.animations, None);
+    }
+
+    #[test]
+    fn loader_names() {
+        let lights = ["hero".to_owned(), String::new()];
+        assert_eq!(names(&lights, "light"), vec!["hero", "light 1"]);
+        let animations = [None, Some("walk".to_owned())];
+        assert_eq!(names(&animations, "animation"), vec!["animation 0", "walk"]);
+    }
+
+    #[test]
+    fn scenes_and_variants() {
+        let document = Document::parse(TRIANGLES.as_bytes()).unwrap();
+        let scenes = names(&document.scenes, "scene");
+        assert_eq!(scenes, vec!["scene 0", "camera"]);
+        assert_eq!(
+            SceneSelector::Name("camera".to_owned()).resolve(&scenes),
+            Some(1)
+        );
+        assert_eq!(SceneSelector::Index(2).resolve(&scenes), None);
+        assert_eq!(document.material_variants(), vec!["red", "blue"]);
+    }
+
+    #[test]
+    fn camera_world_transform() {
+        let document = Document::parse(
+            br#"{
+                "scene": 1,
+                "scenes": [{ "nodes": [] }, { "nodes": [0] }],
+                "nodes": [
+                    { "translation": [0, 1, 0], "children": [1] },
+                    { "camera": 0, "translation": [2, 0, 0], "rotation": [0, 0.70710677, 0, 0.70710677] }
+                ],
+                "cameras": [{ "type": "perspective", "perspective": { "yfov": 0.8, "znear": 0.1 } }]
+            }"#,
+        )
+        .unwrap();
+        assert!(document.camera_nodes(Some(0)).is_empty());
+
+        let cameras = document.camera_nodes(None);
+        assert_eq!(cameras.len(), 1);
+        let close = |a: [f32; 3], b: [f32; 3]| (0..3).all(|i| (a[i] - b[i]).abs() < 1e-5);
+        assert!(close(cameras[0].eye, [2.0, 1.0, 0.0]));
+        // turned a quarter around +Y, the camera looks towards -X
+        assert!(close(cameras[0].forward, [-1.0, 0.0, 0.0]));
+        assert!(close(cameras[0].up, [0.0, 1.0, 0.0]));
+    }
+
+    #[test]
+    fn glb_json_chunk() {
+        let json = TRIANGLES.as_bytes();
+        let mut glb = Vec::new();
+        glb.extend_from_slice(b"glTF");
+        glb.extend_from_slice(&2u32.to_le_bytes());
+        glb.extend_from_slice(&(20 + json.len() as u32).to_le_bytes());
+        glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"JSON");
+        glb.extend_from_slice(json);
+        assert_eq!(Document::parse(&glb).unwrap().meshes.len(), 1);
+    }
+}
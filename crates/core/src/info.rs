@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Bounds, ModelFormat};
+
+/// Statistics of the loaded asset, read from the assimp or gltfio asset. Counts the loader does
+/// not report are `None`, unnamed cameras, lights and animations are listed as e.g.
+/// `"camera 0"`.
+///
+/// gltfio does not keep the accessors and textures of a glTF document, so the triangle, vertex
+/// and texture counts, the cameras and the extensions of glTF files are read from its JSON. They
+/// are `None` when the JSON can not be parsed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelInfo {
+    /// Lowercase extension of the loader format, e.g. `"glb"` or `"fbx"`.
+    pub format: String,
+    /// Format recognized from the content of the file, `None` when the format was picked from
+    /// the file extension.
+    pub detected_format: Option<ModelFormat>,
+    pub triangle_count: Option<u64>,
+    pub vertex_count: Option<u64>,
+    /// Meshes after assimp split them by material, or glTF nodes with a mesh.
+    pub mesh_count: Option<u32>,
+    pub material_count: Option<u32>,
+    /// Only the embedded textures for assimp formats.
+    pub texture_count: Option<u32>,
+    pub node_count: Option<u32>,
+    /// Bounds in the units of the file, before the model is scaled into the `[-1, 1]` cube.
+    pub bounds: Bounds,
+    /// Of assimp formats only the main camera can be rendered from, selected as `"camera 0"`.
+    pub cameras: Option<Vec<String>>,
+    pub lights: Option<Vec<String>>,
+    pub animations: Option<Vec<String>>,
+    /// glTF scenes, the first entry is not necessarily the default scene.
+    pub scenes: Option<Vec<String>>,
+    /// glTF `KHR_materials_variants` variants.
+    pub material_variants: Option<Vec<String>>,
+    /// glTF extensions listed in `extensionsUsed`.
+    pub extensions_used: Vec<String>,
+}
+
+impl ModelInfo {
+    /// Info with only the format and bounds known.
+    pub(crate) fn unknown(format: &str, bounds: Bounds) -> Self {
+        Self {
+            format: format.to_owned(),
+            detected_format: None,
+            triangle_count: None,
+            vertex_count: None,
+            mesh_count: None,
+            material_count: None,
+            texture_count: None,
+            node_count: None,
+            bounds,
+            cameras: None,
+            lights: None,
+            animations: None,
+            scenes: None,
+            material_variants: None,
+            extensions_used: Vec::new(),
+        }
+    }
+}
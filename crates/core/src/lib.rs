@@ -1,23 +1,78 @@
-use core::panic;
+mod animation;
+mod archive;
+mod background;
+mod bounds;
+mod camera;
+mod contact_sheet;
+mod crop;
+mod environment;
+mod error;
+mod exposure;
+mod font;
+mod format;
+mod framing;
+mod gltf;
+mod info;
+mod lighting;
+mod options;
+mod pose;
+mod quality;
+mod resample;
+mod resource;
+mod scene;
+mod vfs;
+
 use std::{cell::Cell, ffi::OsStr, fs, path::Path, rc::Rc};
 
+use archive::Archive;
+use bounds::{add, length, scale, sub};
+use camera::{lens_vertical_fov, FOCAL_LENGTH};
+use environment::EnvironmentResources;
+use exposure::{average_luminance, metered_ev100};
 use filament_bindings::{
     assimp::{post_process, AssimpAsset},
     backend::{Backend, PixelBufferDescriptor, PixelDataFormat, PixelDataType},
     filament::{
-        self, sRGBColor, Aabb, Camera, ClearOptions, Engine, Fov, IndirectLight,
-        IndirectLightBuilder, LightBuilder, Projection, Renderer, Scene, SwapChain,
-        SwapChainConfig, Texture, View, Viewport,
+        self, Aabb, AntiAliasing, ClearOptions, ColorGrading, ColorGradingBuilder, Engine, Fov,
+        MultiSampleAntiAliasingOptions, Projection, Renderer, Scene, SwapChain, SwapChainConfig,
+        View, Viewport,
     },
     glftio::{
-        AssetConfiguration, AssetLoader, MaterialProvider, ResourceConfiguration, ResourceLoader,
+        Animator, AssetConfiguration, AssetLoader, FilamentAsset, MaterialProvider,
+        ResourceConfiguration, ResourceLoader,
     },
-    image::{ktx, KtxBundle},
-    math::{Float3, Mat3f, Mat4f},
+    math::{Float3, Mat4f},
     utils::Entity,
 };
-
-const IDL_TEXTURE_DATA: &'static [u8] = include_bytes!("lightroom_14b_ibl.ktx");
+use gltf::{names, Document, Name};
+use image::RgbaImage;
+use lighting::{create_lights, destroy_lights};
+use resample::{fit_size, resample};
+use serde::{Deserialize, Serialize};
+
+pub use animation::{
+    encode_animation, AnimationFormat, AnimationFrame, PlaybackOptions, TurntableOptions,
+};
+pub use archive::ArchiveSource;
+pub use background::Background;
+pub use bounds::Bounds;
+pub use camera::{CameraPose, CameraPreset, CameraSpec, EmbeddedCamera, ProjectionMode};
+pub use contact_sheet::{ContactSheetOptions, ContactSheetView};
+pub use crop::AutoCrop;
+pub use environment::{Environment, EnvironmentPreset, EnvironmentSource};
+pub use error::{Result, SpaceThumbnailsError};
+pub use exposure::{Exposure, ToneMapping};
+pub use format::ModelFormat;
+pub use framing::{Framing, FramingMode};
+pub use info::ModelInfo;
+pub use lighting::{Light, LightKind, LightingPreset, LightingSpec, SunLight};
+pub use options::{RenderOptions, RenderOptionsBuilder};
+pub use pose::{AnimationPose, AnimationSelector, AnimationTime};
+pub use quality::Quality;
+pub use resample::SizeStrategy;
+pub use resource::ResourceResolver;
+pub use scene::SceneSelector;
+pub use vfs::Vfs;
 
 const ASSIMP_FLAGS: u32 = post_process::GEN_SMOOTH_NORMALS
     | post_process::CALC_TANGENT_SPACE
@@ -32,79 +87,129 @@ pub struct SpaceThumbnailsRenderer {
     // need release
     engine: Engine,
     scene: Scene,
-    ibl_texture: Texture,
-    ibl: IndirectLight,
+    environment_resources: EnvironmentResources,
     swap_chain: SwapChain,
     renderer: Renderer,
     camera_entity: Entity,
-    sunlight_entity: Entity,
+    light_entities: Vec<Entity>,
     view: View,
-    destory_asset: Option<Box<dyn FnOnce(&mut Engine, &mut Scene)>>,
+    destory_asset: Option<DestroyAsset>,
+    animator: Option<AssetAnimator>,
 
     viewport: Viewport,
+    options: RenderOptions,
+    backdrop: Background,
+    /// `backdrop` fitted to the output size it was last composited at.
+    fitted_backdrop: Option<((u32, u32), Background)>,
+    metered_ev100: Option<f32>,
+    color_grading: Option<ColorGrading>,
+    asset_bounds: Option<Bounds>,
+    asset_cameras: Vec<AssetCamera>,
+    model_info: Option<ModelInfo>,
+}
+
+/// Removes the loaded asset from the scene and releases it.
+type DestroyAsset = Box<dyn FnOnce(&mut Engine, &mut Scene)>;
+
+/// Animator of a glTF asset with the renderables it moves.
+struct AssetAnimator {
+    animator: Animator,
+    renderables: Vec<Entity>,
+    /// Joints of the skins, the bounding boxes of skinned renderables only cover the bind pose.
+    joints: Vec<Entity>,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// Camera stored in the model file, already transformed into the normalized model space.
+struct AssetCamera {
+    /// Index and name of the camera in the file.
+    index: usize,
+    name: String,
+    /// Field of view in degrees, `None` for an orthographic camera.
+    fov: Option<f64>,
+    /// Half extent of the view of an orthographic camera.
+    half_extent: f64,
+    /// `fov` and `half_extent` apply to the vertical axis for glTF and to the horizontal axis for
+    /// assimp cameras.
+    vertical: bool,
+    near: f64,
+    transform: Mat4f,
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 #[repr(u8)]
 pub enum RendererBackend {
+    #[default]
     Default = 0,
+    #[serde(rename = "opengl")]
     OpenGL = 1,
     Vulkan = 2,
     Metal = 3,
 }
 
 impl SpaceThumbnailsRenderer {
-    pub fn new(backend: RendererBackend, width: u32, height: u32) -> Self {
+    pub fn new(backend: RendererBackend, width: u32, height: u32) -> Result<Self> {
         unsafe {
-            let mut engine = Engine::create(match backend {
+            let engine = Engine::create(match backend {
                 RendererBackend::Default => Backend::DEFAULT,
                 RendererBackend::OpenGL => Backend::OPENGL,
                 RendererBackend::Vulkan => Backend::VULKAN,
                 RendererBackend::Metal => Backend::METAL,
             })
-            .unwrap();
-            let mut scene = engine.create_scene().unwrap();
-            let mut swap_chain = engine
-                .create_headless_swap_chain(width, height, SwapChainConfig::TRANSPARENT)
-                .unwrap();
-            let mut renderer = engine.create_renderer().unwrap();
-            let mut view = engine.create_view().unwrap();
-            let mut entity_manager = engine.get_entity_manager().unwrap();
-            let camera_entity = entity_manager.create();
-            let mut camera = engine.create_camera(&camera_entity).unwrap();
-            let ibl_texture = ktx::create_texture(
-                &mut engine,
-                KtxBundle::from(IDL_TEXTURE_DATA).unwrap(),
-                false,
-            )
-            .unwrap();
-
-            let mut ibl = IndirectLightBuilder::new()
-                .unwrap()
-                .reflections(&ibl_texture)
-                .intensity(50000.0)
-                .rotation(&Mat3f::rotation(-90.0, Float3::new(0.0, 1.0, 0.0)))
-                .build(&mut engine)
-                .unwrap();
-            scene.set_indirect_light(&mut ibl);
-
-            let sunlight_entity = entity_manager.create();
-            LightBuilder::new(filament::LightType::SUN)
-                .unwrap()
-                .color(&sRGBColor(Float3::new(0.98, 0.92, 0.89)).to_linear_fast())
-                .intensity(100000.0)
-                .direction(&Float3::new(0.6, -1.0, -0.8).normalize())
-                .cast_shadows(true)
-                .sun_angular_radius(1.0)
-                .sun_halo_size(2.0)
-                .sun_halo_falloff(80.0)
-                .build(&mut engine, &sunlight_entity)
-                .unwrap();
-
-            scene.add_entity(&sunlight_entity);
+            .ok_or(SpaceThumbnailsError::BackendUnavailable(backend))?;
+            // destroys what was created so far when one of the resources can not be
+            let mut parts = RendererParts {
+                engine: Some(engine),
+                scene: None,
+                swap_chain: None,
+                renderer: None,
+                view: None,
+                camera_entity: None,
+                environment_resources: None,
+                light_entities: Vec::new(),
+            };
+            let engine = parts.engine.as_mut().unwrap();
+            let scene = parts.scene.insert(
+                engine
+                    .create_scene()
+                    .ok_or(SpaceThumbnailsError::ResourceCreation("scene"))?,
+            );
+            let swap_chain = parts.swap_chain.insert(
+                engine
+                    .create_headless_swap_chain(width, height, SwapChainConfig::TRANSPARENT)
+                    .ok_or(SpaceThumbnailsError::ResourceCreation("swap chain"))?,
+            );
+            let renderer = parts.renderer.insert(
+                engine
+                    .create_renderer()
+                    .ok_or(SpaceThumbnailsError::ResourceCreation("renderer"))?,
+            );
+            let view = parts.view.insert(
+                engine
+                    .create_view()
+                    .ok_or(SpaceThumbnailsError::ResourceCreation("view"))?,
+            );
+            let mut entity_manager = engine
+                .get_entity_manager()
+                .ok_or(SpaceThumbnailsError::ResourceCreation("entity manager"))?;
+            let camera_entity = *parts.camera_entity.insert(entity_manager.create());
+            let mut camera = engine
+                .create_camera(&camera_entity)
+                .ok_or(SpaceThumbnailsError::ResourceCreation("camera"))?;
+            let environment_resources =
+                parts
+                    .environment_resources
+                    .insert(EnvironmentResources::create(
+                        engine,
+                        &Environment::default(),
+                    )?);
+            scene.set_indirect_light(&mut environment_resources.ibl);
+
+            parts.light_entities = create_lights(engine, scene, &LightingSpec::default())?;
 
             view.set_camera(&mut camera);
-            view.set_scene(&mut scene);
+            view.set_scene(scene);
+            // backgrounds are composited after the readback, the frame itself keeps its alpha
             renderer.set_clear_options(&ClearOptions {
                 clear_color: [0.0, 0.0, 0.0, 0.0].into(),
                 clear: true,
@@ -121,214 +226,811 @@ impl SpaceThumbnailsRenderer {
             view.set_viewport(&viewport);
 
             // warming up
-            renderer.begin_frame(&mut swap_chain);
-            renderer.render(&mut view);
+            renderer.begin_frame(swap_chain);
+            renderer.render(view);
             renderer.end_frame();
             engine.flush_and_wait();
 
-            Self {
-                engine,
-                scene,
-                ibl_texture,
-                ibl,
-                swap_chain,
-                renderer,
-                camera_entity,
-                sunlight_entity,
-                view,
+            // every part was created above
+            Ok(Self {
+                engine: parts.engine.take().unwrap(),
+                scene: parts.scene.take().unwrap(),
+                environment_resources: parts.environment_resources.take().unwrap(),
+                swap_chain: parts.swap_chain.take().unwrap(),
+                renderer: parts.renderer.take().unwrap(),
+                camera_entity: parts.camera_entity.take().unwrap(),
+                light_entities: std::mem::take(&mut parts.light_entities),
+                metered_ev100: None,
+                color_grading: None,
+                view: parts.view.take().unwrap(),
                 destory_asset: None,
                 viewport,
-            }
+                options: RenderOptions::builder()
+                    .backend(backend)
+                    .size(width, height)
+                    .build(),
+                backdrop: Background::default(),
+                fitted_backdrop: None,
+                asset_bounds: None,
+                asset_cameras: Vec::new(),
+                model_info: None,
+                animator: None,
+            })
         }
     }
 
-    pub fn load_asset_from_file(&mut self, filepath: impl AsRef<Path>) -> Option<&mut Self> {
-        if matches!(filepath.as_ref().extension(), Some(e) if e == "gltf" || e == "glb") {
-            let data = fs::read(&filepath).ok()?;
-            self.load_gltf_asset(
-                &data,
-                filepath.as_ref().file_name()?,
-                Some(filepath.as_ref()),
-            )
+    /// Creates a renderer and applies all of `options`.
+    pub fn with_options(options: &RenderOptions) -> Result<Self> {
+        let mut renderer = Self::new(options.backend, options.width, options.height)?;
+        renderer.set_options(options)?;
+        Ok(renderer)
+    }
+
+    /// Applies `options`, except for the backend which is fixed when the renderer is created.
+    /// Resources are only recreated for the options that changed.
+    pub fn set_options(&mut self, options: &RenderOptions) -> Result<&mut Self> {
+        if options.quality != self.options.quality {
+            self.set_quality(options.quality)?;
+        }
+        if (options.width, options.height) != self.get_size() {
+            self.set_size(options.width, options.height)?;
+        }
+        if options.environment != self.options.environment {
+            self.set_environment(options.environment.clone())?;
+        }
+        if options.lighting != self.options.lighting {
+            self.set_lighting(options.lighting.clone())?;
+        }
+        if options.tone_mapping != self.options.tone_mapping {
+            self.set_tone_mapping(options.tone_mapping)?;
+        }
+        if options.background != self.options.background {
+            self.set_background(options.background.clone())?;
+        }
+        if options.exposure != self.options.exposure {
+            self.set_exposure(options.exposure)?;
+        }
+        self.options.scene = options.scene.clone();
+        self.options.material_variant = options.material_variant.clone();
+        if options.animation != self.options.animation {
+            self.set_animation_pose(options.animation.clone())?;
+        }
+        self.options.camera = options.camera;
+        self.options.embedded_camera = options.embedded_camera.clone();
+        self.options.framing = options.framing;
+        self.options.projection = options.projection;
+        self.options.auto_crop = options.auto_crop;
+        self.update_camera()?;
+        Ok(self)
+    }
+
+    pub fn get_options(&self) -> &RenderOptions {
+        &self.options
+    }
+
+    /// Loads a model file, its format is detected from the content and falls back to the file
+    /// extension, so misnamed and extension-less files load too. Misnamed files are copied under
+    /// the detected extension with the `.mtl` files and textures of `.obj` models only, the
+    /// external textures of other formats are not found.
+    pub fn load_asset_from_file(&mut self, filepath: impl AsRef<Path>) -> Result<&mut Self> {
+        let filepath = filepath.as_ref();
+        let io_error = |source| SpaceThumbnailsError::Io {
+            path: filepath.to_owned(),
+            source,
+        };
+        let detected = ModelFormat::detect_file(filepath).map_err(io_error)?;
+        let named_extension = file_extension(filepath).ok();
+        let extension = resolve_format(detected, named_extension.as_deref())?;
+        if extension == "gltf" || extension == "glb" {
+            let data = fs::read(filepath).map_err(io_error)?;
+            let filename = filepath
+                .file_name()
+                .ok_or_else(|| SpaceThumbnailsError::InvalidFilename(filepath.to_owned()))?;
+            self.load_gltf_asset(&data, filename, Some(filepath))
         } else {
-            let asset =
-                AssimpAsset::from_file_with_flags(&mut self.engine, filepath, ASSIMP_FLAGS).ok()?;
-            self.load_assimp_asset(asset)
+            if named_extension.as_deref() == Some(extension.as_str()) {
+                self.load_assimp_file(filepath, &extension, detected)
+            } else {
+                // assimp picks its importer from the extension, misnamed files are copied with
+                // the detected one next to the files they reference
+                let data = fs::read(filepath).map_err(io_error)?;
+                let filename = filepath
+                    .file_name()
+                    .ok_or_else(|| SpaceThumbnailsError::InvalidFilename(filepath.to_owned()))?;
+                let directory = vfs::DirectoryVfs(
+                    filepath
+                        .parent()
+                        .unwrap_or_else(|| Path::new(""))
+                        .to_owned(),
+                );
+                let (_directory, model) = vfs::materialize(
+                    &directory,
+                    &data,
+                    &Path::new(filename).with_extension(&extension),
+                    vfs::LIMITS,
+                )?;
+                self.load_assimp_file(&model, &extension, detected)
+            }
         }
     }
 
+    fn load_assimp_file(
+        &mut self,
+        filepath: &Path,
+        extension: &str,
+        detected: Option<ModelFormat>,
+    ) -> Result<&mut Self> {
+        let asset = AssimpAsset::from_file_with_flags(&mut self.engine, filepath, ASSIMP_FLAGS)
+            .map_err(|err| assimp_error(err, extension))?;
+        self.add_assimp_asset(asset, extension, detected)
+    }
+
+    fn load_assimp_memory(
+        &mut self,
+        buffer: &[u8],
+        extension: &str,
+        detected: Option<ModelFormat>,
+    ) -> Result<&mut Self> {
+        let asset =
+            AssimpAsset::from_memory_with_flags(&mut self.engine, buffer, extension, ASSIMP_FLAGS)
+                .map_err(|err| assimp_error(err, extension))?;
+        self.add_assimp_asset(asset, extension, detected)
+    }
+
+    /// Loads a model from memory, its format is detected from the content and falls back to the
+    /// extension of `filename`.
     pub fn load_asset_from_memory(
         &mut self,
         buffer: &[u8],
         filename: impl AsRef<OsStr>,
-    ) -> Option<&mut Self> {
-        if matches!(Path::new(filename.as_ref()).extension(), Some(e) if e == "gltf" || e == "glb")
-        {
-            self.load_gltf_asset(buffer, filename.as_ref(), None)
+    ) -> Result<&mut Self> {
+        let extension = file_extension(Path::new(filename.as_ref())).ok();
+        self.load_asset_from_bytes(buffer, extension.as_deref())
+    }
+
+    /// Loads a model from memory without a file name, its format is detected from the content.
+    /// `extension_hint`, e.g. `"obj"`, is used for formats that are not recognized.
+    pub fn load_asset_from_bytes(
+        &mut self,
+        buffer: &[u8],
+        extension_hint: Option<&str>,
+    ) -> Result<&mut Self> {
+        let detected = ModelFormat::detect(buffer);
+        let extension = resolve_format(detected, extension_hint)?;
+        if extension == "gltf" || extension == "glb" {
+            self.load_gltf_asset(buffer, OsStr::new(""), None)
         } else {
-            let asset = AssimpAsset::from_memory_with_flags(
-                &mut self.engine,
+            self.load_assimp_memory(buffer, &extension, detected)
+        }
+    }
+
+    /// Like `load_asset_from_memory`, the external files referenced by a glTF document are read
+    /// from `resolver`. Other formats ignore the resolver.
+    pub fn load_asset_from_memory_with_resolver(
+        &mut self,
+        buffer: &[u8],
+        filename: impl AsRef<OsStr>,
+        resolver: &dyn ResourceResolver,
+    ) -> Result<&mut Self> {
+        let filename = filename.as_ref();
+        let extension = file_extension(Path::new(filename)).ok();
+        let extension = resolve_format(ModelFormat::detect(buffer), extension.as_deref())?;
+        if extension == "gltf" || extension == "glb" {
+            self.load_gltf_asset_with_resolver(buffer, filename, resolver)
+        } else {
+            self.load_asset_from_memory(buffer, filename)
+        }
+    }
+
+    /// Loads the model stored at `filename` in `vfs`, `buffer` being its content. The files the
+    /// model references are read from `vfs`, relative to `filename`. Assimp only reads from the
+    /// disk, so these files are copied into a temporary directory while the model is loaded.
+    pub fn load_asset_from_memory_with_vfs(
+        &mut self,
+        buffer: &[u8],
+        filename: impl AsRef<Path>,
+        vfs: &dyn Vfs,
+    ) -> Result<&mut Self> {
+        let filename = filename.as_ref();
+        let detected = ModelFormat::detect(buffer);
+        let extension = resolve_format(detected, file_extension(filename).ok().as_deref())?;
+        if extension == "gltf" || extension == "glb" {
+            let name = filename
+                .file_name()
+                .ok_or_else(|| SpaceThumbnailsError::InvalidFilename(filename.to_owned()))?;
+            let resolver = |uri: &str| {
+                vfs::resolve_path(filename, &vfs::decode_uri(uri))
+                    .and_then(|path| vfs.open(&path).ok())
+            };
+            self.load_gltf_asset_with_resolver(buffer, name, &resolver)
+        } else {
+            // written with the extension of the detected format so that assimp picks its importer
+            let (_directory, model) = vfs::materialize(
+                vfs,
                 buffer,
-                filename.as_ref().to_str()?,
-                ASSIMP_FLAGS,
-            )
-            .ok()?;
-            self.load_assimp_asset(asset)
+                &filename.with_extension(&extension),
+                vfs::LIMITS,
+            )?;
+            self.load_assimp_file(&model, &extension, detected)
         }
     }
 
-    pub fn load_assimp_asset(&mut self, mut asset: AssimpAsset) -> Option<&mut Self> {
+    /// Loads a model from a ZIP archive, the files it references are read from the archive too.
+    /// When `entry` is `None` the primary model of the archive is picked, preferring glTF files
+    /// and the least nested ones.
+    pub fn load_asset_from_archive<'a>(
+        &mut self,
+        archive: impl Into<ArchiveSource<'a>>,
+        entry: Option<&str>,
+    ) -> Result<&mut Self> {
+        let archive = Archive::open(archive.into())?;
+        let entry = match entry {
+            Some(entry) => vfs::normalize_path(Path::new(entry))
+                .ok_or_else(|| SpaceThumbnailsError::MissingArchiveEntry(entry.to_owned()))?,
+            None => archive
+                .primary_model()
+                .ok_or(SpaceThumbnailsError::NoModelInArchive)?,
+        };
+        if matches!(archive.size(&entry), Some(size) if size > vfs::LIMITS.bytes) {
+            return Err(SpaceThumbnailsError::ResourceLimit {
+                files: vfs::LIMITS.files,
+                bytes: vfs::LIMITS.bytes,
+            });
+        }
+        let buffer = archive.open(&entry).map_err(|_| {
+            SpaceThumbnailsError::MissingArchiveEntry(entry.to_string_lossy().into_owned())
+        })?;
+        self.load_asset_from_memory_with_vfs(&buffer, &entry, &archive)
+    }
+
+    pub fn load_assimp_asset(&mut self, asset: AssimpAsset) -> Result<&mut Self> {
+        self.add_assimp_asset(asset, "assimp", None)
+    }
+
+    fn add_assimp_asset(
+        &mut self,
+        mut asset: AssimpAsset,
+        format: &str,
+        detected_format: Option<ModelFormat>,
+    ) -> Result<&mut Self> {
         self.destory_opened_asset();
 
         unsafe {
             let aabb = asset.get_aabb();
-            let transform = fit_into_unit_cube(aabb);
+            let bounds = bounds_from_aabb(aabb);
+            let transform = fit_into_unit_cube(&bounds);
 
-            let mut transform_manager = self.engine.get_transform_manager()?;
+            let mut transform_manager = self
+                .engine
+                .get_transform_manager()
+                .ok_or(SpaceThumbnailsError::ResourceCreation("transform manager"))?;
             let root_entity = asset.get_root_entity();
-            let root_transform_instance = transform_manager.get_instance(root_entity)?;
+            let root_transform_instance = transform_manager
+                .get_instance(root_entity)
+                .ok_or(SpaceThumbnailsError::ResourceCreation("root transform"))?;
             transform_manager.set_transform_float(&root_transform_instance, &transform);
 
             self.scene.add_entities(asset.get_renderables());
 
             self.scene.add_entity(root_entity);
 
-            let mut camera = self
-                .engine
-                .get_camera_component(&self.camera_entity)
-                .unwrap();
-
-            camera.set_exposure_physical(16.0, 1.0 / 125.0, 100.0);
-
-            if let Some(camera_info) = asset.get_main_camera() {
-                let aspect = self.viewport.width as f64 / self.viewport.height as f64;
-                if camera_info.horizontal_fov != 0.0 {
-                    camera.set_projection_fov_direction(
-                        camera_info.horizontal_fov,
-                        aspect,
-                        0.1,
-                        f64::INFINITY,
-                        Fov::HORIZONTAL,
-                    );
-                } else {
-                    camera.set_projection(
-                        Projection::ORTHO,
-                        -camera_info.orthographic_width,
-                        camera_info.orthographic_width,
-                        -camera_info.orthographic_width / aspect,
-                        camera_info.orthographic_width / aspect,
-                        0.1,
-                        100000.0,
-                    );
-                }
-                transform_manager.set_transform_float(
-                    &transform_manager.get_instance(&self.camera_entity).unwrap(),
-                    &(transform
+            self.asset_bounds = Some(normalize_bounds(&bounds));
+            self.model_info = Some(ModelInfo {
+                detected_format,
+                // faces are triangulated on import
+                triangle_count: Some(asset.get_face_count() as u64),
+                vertex_count: Some(asset.get_vertex_count() as u64),
+                mesh_count: Some(asset.get_mesh_count() as u32),
+                material_count: Some(asset.get_material_count() as u32),
+                texture_count: Some(asset.get_texture_count() as u32),
+                node_count: Some(asset.get_node_count() as u32),
+                cameras: Some(names(&asset.get_camera_names(), "camera")),
+                lights: Some(names(&asset.get_light_names(), "light")),
+                animations: Some(names(&asset.get_animation_names(), "animation")),
+                ..ModelInfo::unknown(format, bounds)
+            });
+            self.asset_cameras = asset
+                .get_main_camera()
+                .map(|camera_info| AssetCamera {
+                    index: 0,
+                    name: "camera 0".to_owned(),
+                    fov: Some(camera_info.horizontal_fov).filter(|fov| *fov != 0.0),
+                    half_extent: camera_info.orthographic_width,
+                    vertical: false,
+                    near: 0.1,
+                    transform: transform
                         * Mat4f::look_at(
                             &camera_info.position,
                             &camera_info.look_at,
                             &camera_info.up,
-                        )),
-                )
-            } else {
-                setup_camera_surround_view(&mut camera, &aabb.transform(transform), &self.viewport);
-            }
+                        ),
+                })
+                .into_iter()
+                .collect();
 
             self.destory_asset = Some(Box::new(move |engine, scene| {
                 scene.remove_entities(asset.get_renderables());
                 scene.remove_entity(asset.get_root_entity());
                 asset.destory(engine)
             }));
+            self.update_animation()?;
+            self.update_camera()?;
+            self.update_exposure()?;
         }
 
-        Some(self)
+        Ok(self)
     }
 
+    /// Loads a glTF asset, binary and JSON documents are told apart by their content whatever
+    /// the extension of `_filename`.
     pub fn load_gltf_asset(
         &mut self,
         data: &[u8],
-        filename: &OsStr,
+        _filename: &OsStr,
+        filepath: Option<&Path>,
+    ) -> Result<&mut Self> {
+        self.add_gltf_asset(data, filepath, None)
+    }
+
+    /// Loads a glTF asset from memory, every external URI of the document is read from
+    /// `resolver`.
+    pub fn load_gltf_asset_with_resolver(
+        &mut self,
+        data: &[u8],
+        _filename: &OsStr,
+        resolver: &dyn ResourceResolver,
+    ) -> Result<&mut Self> {
+        self.add_gltf_asset(data, None, Some(resolver))
+    }
+
+    fn add_gltf_asset(
+        &mut self,
+        data: &[u8],
         filepath: Option<&Path>,
-    ) -> Option<&mut Self> {
+        resolver: Option<&dyn ResourceResolver>,
+    ) -> Result<&mut Self> {
         self.destory_opened_asset();
 
-        let binary = matches!(Path::new(filename).extension(), Some(e) if e == "glb");
+        let binary = data.starts_with(b"glTF");
 
         let filepath_str = filepath.and_then(|p| p.to_str().map(|s| s.to_owned()));
 
+        let document = Document::parse(data);
+        let scene = match &self.options.scene {
+            Some(scene) => {
+                let scenes = document
+                    .as_ref()
+                    .map(|document| names(&document.scenes, "scene"))
+                    .unwrap_or_default();
+                let index = scene
+                    .resolve(&scenes)
+                    .ok_or_else(|| SpaceThumbnailsError::SceneNotFound(scene.clone()))?;
+                Some(index)
+            }
+            None => None,
+        };
+        let material_variant = match &self.options.material_variant {
+            Some(variant) => {
+                let index = document
+                    .as_ref()
+                    .and_then(|document| {
+                        document
+                            .material_variants()
+                            .iter()
+                            .position(|name| name == variant)
+                    })
+                    .ok_or_else(|| {
+                        SpaceThumbnailsError::MaterialVariantNotFound(variant.clone())
+                    })?;
+                Some(index)
+            }
+            None => None,
+        };
+
+        // checked before the asset is created, a missing animation leaves the scene untouched
+        if let (Some(pose), Some(document)) = (&self.options.animation, &document) {
+            let animations = document
+                .animations
+                .iter()
+                .map(|animation| (animation.name().map(|name| name.to_owned()), 0.0))
+                .collect::<Vec<_>>();
+            pose.select(&animations)?;
+        }
+
         unsafe {
-            let materials = MaterialProvider::create_ubershader_loader(&mut self.engine)?;
-            let mut entity_manager = self.engine.get_entity_manager()?;
-            let mut transform_manager = self.engine.get_transform_manager()?;
+            let materials = MaterialProvider::create_ubershader_loader(&mut self.engine)
+                .ok_or(SpaceThumbnailsError::ResourceCreation("material provider"))?;
+            let mut entity_manager = self
+                .engine
+                .get_entity_manager()
+                .ok_or(SpaceThumbnailsError::ResourceCreation("entity manager"))?;
             let mut loader = AssetLoader::create(AssetConfiguration {
                 engine: &mut self.engine,
                 materials,
                 entities: Some(&mut entity_manager),
                 default_node_name: None,
-            })?;
+            })
+            .ok_or(SpaceThumbnailsError::ResourceCreation("gltf asset loader"))?;
 
-            let mut asset = if binary {
-                loader.create_asset_from_binary(&data)?
+            let asset = if binary {
+                loader.create_asset_from_binary(data)
             } else {
-                loader.create_asset_from_json(&data)?
+                loader.create_asset_from_json(data)
+            };
+            let mut asset = match asset {
+                Some(asset) => asset,
+                None => {
+                    loader.destroy_materials();
+                    return Err(SpaceThumbnailsError::CorruptAsset {
+                        format: if binary { "glb" } else { "gltf" }.to_owned(),
+                        source: "gltfio could not parse the asset".into(),
+                    });
+                }
             };
 
-            let uris = asset.get_resource_uris();
-            let has_external_resource = uris
-                .map(|uris| uris.into_iter().any(|uri| !is_base64_data_uri(&uri)))
-                .unwrap_or(false);
+            let mut resources = Vec::new();
+            if filepath_str.is_none() {
+                let external_uris = asset
+                    .get_resource_uris()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|uri| !is_base64_data_uri(uri));
+                for uri in external_uris {
+                    match resolver.and_then(|resolver| resolver.resolve(&uri)) {
+                        Some(resource) => resources.push((uri, resource)),
+                        None => {
+                            loader.destroy_asset(&asset);
+                            loader.destroy_materials();
+                            return Err(SpaceThumbnailsError::MissingExternalResource(uri));
+                        }
+                    }
+                }
+            }
 
-            if filepath_str.is_none() && has_external_resource {
-                return None;
+            let (entities, bounds) = match self.place_gltf_asset(
+                &mut asset,
+                filepath_str,
+                resources,
+                scene,
+                material_variant,
+            ) {
+                Ok(placed) => placed,
+                Err(err) => {
+                    loader.destroy_asset(&asset);
+                    loader.destroy_materials();
+                    return Err(err);
+                }
+            };
+            let joints = (0..asset.get_skin_count())
+                .flat_map(|skin| asset.get_joints_at(skin))
+                .filter(|joint| scene.is_none() || entities.contains(joint))
+                .collect();
+            self.animator = asset.get_animator().map(|animator| AssetAnimator {
+                animator,
+                renderables: entities.clone(),
+                joints,
+            });
+
+            self.scene.add_entities(&entities);
+
+            self.asset_bounds = Some(normalize_bounds(&bounds));
+            let format = if binary { "glb" } else { "gltf" };
+            let info = match &document {
+                Some(document) => document.model_info(format, bounds),
+                None => ModelInfo::unknown(format, bounds),
+            };
+            let mut renderable_manager = self
+                .engine
+                .get_renderable_manager()
+                .ok_or(SpaceThumbnailsError::ResourceCreation("renderable manager"))?;
+            let all_entities = asset.get_entities();
+            let lights = asset
+                .get_light_entities()
+                .iter()
+                .map(|light| asset.get_name(light))
+                .collect::<Vec<_>>();
+            let animations = self
+                .animations()
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>();
+            self.model_info = Some(ModelInfo {
+                // `None` when the document was only recognized from its extension
+                detected_format: ModelFormat::detect(data)
+                    .filter(|format| matches!(format, ModelFormat::Gltf | ModelFormat::Glb)),
+                // gltfio creates a renderable for every node with a mesh
+                mesh_count: Some(
+                    all_entities
+                        .iter()
+                        .filter(|entity| renderable_manager.get_instance(entity).is_some())
+                        .count() as u32,
+                ),
+                material_count: Some(asset.get_material_instance_count() as u32),
+                node_count: Some(all_entities.len() as u32),
+                lights: Some(names(&lights, "light")),
+                animations: Some(names(&animations, "animation")),
+                ..info
+            });
+            if let Some(document) = &document {
+                self.asset_cameras = gltf_cameras(document, scene, &bounds);
             }
 
-            ResourceLoader::create(ResourceConfiguration {
-                engine: &mut self.engine,
-                gltf_path: filepath_str,
-                normalize_skinning_weights: true,
-                recompute_bounding_boxes: false,
-                ignore_bind_transform: false,
-            })
-            .unwrap()
-            .load_resources(&mut asset);
+            self.destory_asset = Some(Box::new(move |_engine, scene| {
+                scene.remove_entities(&entities);
+                loader.destroy_asset(&asset);
+                loader.destroy_materials();
+            }));
+            self.update_animation()?;
+            self.update_camera()?;
+            self.update_exposure()?;
+        }
 
-            asset.release_source_data();
+        Ok(self)
+    }
 
-            let aabb = asset.get_bounding_box();
-            let transform = fit_into_unit_cube(&aabb);
-            let root_transform_instance = transform_manager.get_instance(&asset.get_root())?;
+    /// Loads the resources of a created glTF asset and fits it into the normalized model space.
+    /// Returns the entities of the selected scene and their bounds in the units of the file. The
+    /// caller destroys the asset when this fails.
+    unsafe fn place_gltf_asset(
+        &mut self,
+        asset: &mut FilamentAsset,
+        filepath: Option<String>,
+        resources: Vec<(String, Vec<u8>)>,
+        scene: Option<usize>,
+        material_variant: Option<usize>,
+    ) -> Result<(Vec<Entity>, Bounds)> {
+        let mut resource_loader = ResourceLoader::create(ResourceConfiguration {
+            engine: &mut self.engine,
+            gltf_path: filepath,
+            normalize_skinning_weights: true,
+            recompute_bounding_boxes: false,
+            ignore_bind_transform: false,
+        })
+        .ok_or(SpaceThumbnailsError::ResourceCreation(
+            "gltf resource loader",
+        ))?;
+        for (uri, resource) in resources {
+            resource_loader.add_resource_data(&uri, resource);
+        }
+        resource_loader.load_resources(asset);
 
-            transform_manager.set_transform_float(&root_transform_instance, &transform);
+        asset.release_source_data();
+        if let Some(variant) = material_variant {
+            asset.apply_material_variant(variant);
+        }
+        let entities = match scene {
+            Some(scene) => asset.get_scene_entities(scene),
+            None => asset.get_entities().to_vec(),
+        };
+
+        let bounds = match scene {
+            // the bounding box of the asset covers every scene
+            Some(_) => renderables_bounds(&mut self.engine, &entities)?
+                .unwrap_or_else(|| bounds_from_aabb(&asset.get_bounding_box())),
+            None => bounds_from_aabb(&asset.get_bounding_box()),
+        };
+        let transform = fit_into_unit_cube(&bounds);
+        let mut transform_manager = self
+            .engine
+            .get_transform_manager()
+            .ok_or(SpaceThumbnailsError::ResourceCreation("transform manager"))?;
+        let root_transform_instance = transform_manager
+            .get_instance(&asset.get_root())
+            .ok_or(SpaceThumbnailsError::ResourceCreation("root transform"))?;
+
+        transform_manager.set_transform_float(&root_transform_instance, &transform);
+
+        Ok((entities, bounds))
+    }
 
-            self.scene.add_entities(asset.get_entities());
+    /// Sets the viewpoint used for the loaded asset and for assets loaded later. `None` restores
+    /// the default: the camera stored in the file if there is one, otherwise a three-quarter view.
+    pub fn set_camera(&mut self, camera: Option<CameraSpec>) -> Result<&mut Self> {
+        self.options.camera = camera;
+        self.update_camera()?;
+        Ok(self)
+    }
 
-            let mut camera = self
-                .engine
-                .get_camera_component(&self.camera_entity)
-                .unwrap();
+    pub fn get_camera(&self) -> Option<CameraSpec> {
+        self.options.camera
+    }
 
-            camera.set_exposure_physical(16.0, 1.0 / 125.0, 100.0);
+    pub fn set_framing(&mut self, framing: Framing) -> Result<&mut Self> {
+        self.options.framing = framing;
+        self.update_camera()?;
+        Ok(self)
+    }
 
-            setup_camera_surround_view(&mut camera, &aabb.transform(transform), &self.viewport);
+    pub fn get_framing(&self) -> Framing {
+        self.options.framing
+    }
 
-            self.destory_asset = Some(Box::new(move |_engine, scene| {
-                scene.remove_entities(asset.get_entities());
-                loader.destroy_asset(&asset);
-                loader.destroy_materials();
-                core::mem::drop(loader);
-            }));
+    /// Selects the camera stored in the model file that is used when no camera is set.
+    pub fn set_embedded_camera(&mut self, embedded_camera: EmbeddedCamera) -> Result<&mut Self> {
+        self.options.embedded_camera = embedded_camera;
+        self.update_camera()?;
+        Ok(self)
+    }
+
+    pub fn get_embedded_camera(&self) -> &EmbeddedCamera {
+        &self.options.embedded_camera
+    }
+
+    fn asset_camera_index(&self) -> Result<Option<usize>> {
+        let position = |found: &dyn Fn(&AssetCamera) -> bool| {
+            self.asset_cameras
+                .iter()
+                .position(found)
+                .map(Some)
+                .ok_or_else(|| {
+                    SpaceThumbnailsError::CameraNotFound(self.options.embedded_camera.clone())
+                })
+        };
+        match &self.options.embedded_camera {
+            EmbeddedCamera::First if self.asset_cameras.is_empty() => Ok(None),
+            EmbeddedCamera::First => Ok(Some(0)),
+            EmbeddedCamera::Index(index) => position(&|camera| camera.index == *index),
+            EmbeddedCamera::Name(name) => position(&|camera| camera.name == *name),
+            EmbeddedCamera::Ignore => Ok(None),
         }
+    }
 
-        Some(self)
+    /// Forcing `ProjectionMode::Orthographic` also replaces cameras stored in the model file.
+    pub fn set_projection(&mut self, projection: ProjectionMode) -> Result<&mut Self> {
+        self.options.projection = projection;
+        self.update_camera()?;
+        Ok(self)
     }
 
-    pub fn take_screenshot_sync(&mut self, output_memory: &mut [u8]) -> usize {
+    pub fn get_projection(&self) -> ProjectionMode {
+        self.options.projection
+    }
+
+    fn update_camera(&mut self) -> Result<()> {
+        let bounds = match self.asset_bounds {
+            Some(bounds) => bounds,
+            None => return Ok(()),
+        };
+
+        unsafe {
+            let mut camera = self
+                .engine
+                .get_camera_component(&self.camera_entity)
+                .ok_or(SpaceThumbnailsError::ResourceCreation("camera"))?;
+            let aspect = self.viewport.width as f64 / self.viewport.height as f64;
+
+            let asset_camera = match self.options.camera {
+                None => self
+                    .asset_camera_index()?
+                    .map(|index| &self.asset_cameras[index]),
+                Some(_) => None,
+            };
+            match (self.options.camera, asset_camera) {
+                (None, Some(asset_camera))
+                    if self.options.projection == ProjectionMode::Perspective =>
+                {
+                    let direction = if asset_camera.vertical {
+                        Fov::VERTICAL
+                    } else {
+                        Fov::HORIZONTAL
+                    };
+                    if let Some(fov) = asset_camera.fov {
+                        camera.set_projection_fov_direction(
+                            fov,
+                            aspect,
+                            asset_camera.near,
+                            f64::INFINITY,
+                            direction,
+                        );
+                    } else {
+                        let (half_width, half_height) = if asset_camera.vertical {
+                            (asset_camera.half_extent * aspect, asset_camera.half_extent)
+                        } else {
+                            (asset_camera.half_extent, asset_camera.half_extent / aspect)
+                        };
+                        camera.set_projection(
+                            Projection::ORTHO,
+                            -half_width,
+                            half_width,
+                            -half_height,
+                            half_height,
+                            asset_camera.near,
+                            100000.0,
+                        );
+                    }
+                    let mut transform_manager = self
+                        .engine
+                        .get_transform_manager()
+                        .ok_or(SpaceThumbnailsError::ResourceCreation("transform manager"))?;
+                    let camera_transform_instance = transform_manager
+                        .get_instance(&self.camera_entity)
+                        .ok_or(SpaceThumbnailsError::ResourceCreation("camera transform"))?;
+                    transform_manager
+                        .set_transform_float(&camera_transform_instance, &asset_camera.transform);
+                }
+                (spec, _) => match self.options.projection {
+                    ProjectionMode::Perspective => {
+                        let pose = spec.unwrap_or_default().resolve(
+                            &bounds,
+                            &self.options.framing,
+                            aspect as f32,
+                            lens_vertical_fov(FOCAL_LENGTH),
+                        );
+                        camera.set_lens_projection(FOCAL_LENGTH, aspect, 0.01, f64::INFINITY);
+                        camera.look_at_up(&pose.eye.into(), &pose.target.into(), &pose.up.into());
+                    }
+                    ProjectionMode::Orthographic => {
+                        let ortho = spec.unwrap_or_default().resolve_orthographic(
+                            &bounds,
+                            &self.options.framing,
+                            aspect as f32,
+                        );
+                        camera.set_projection(
+                            Projection::ORTHO,
+                            -ortho.half_width as f64,
+                            ortho.half_width as f64,
+                            -ortho.half_height as f64,
+                            ortho.half_height as f64,
+                            ortho.near as f64,
+                            ortho.far as f64,
+                        );
+                        camera.look_at_up(
+                            &ortho.pose.eye.into(),
+                            &ortho.pose.target.into(),
+                            &ortho.pose.up.into(),
+                        );
+                    }
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn take_screenshot_sync(&mut self, output_memory: &mut [u8]) -> Result<usize> {
         let byte_count = self.get_screenshot_size_in_byte();
 
         if output_memory.len() < byte_count {
-            panic!("Output memory space is not enough to take screenshot.")
+            return Err(SpaceThumbnailsError::BufferTooSmall {
+                required: byte_count,
+                provided: output_memory.len(),
+            });
+        }
+
+        if let (Exposure::Auto { compensation }, None, Some(_)) =
+            (self.options.exposure, self.metered_ev100, self.asset_bounds)
+        {
+            self.meter_exposure(compensation)?;
+        }
+
+        let (width, height) = self.get_size();
+        if (self.viewport.width, self.viewport.height) == (width, height)
+            && self.options.auto_crop.is_none()
+        {
+            self.render_into(&mut output_memory[..byte_count])?;
+        } else {
+            let mut buffer = vec![0; self.get_render_size_in_byte()];
+            self.render_into(&mut buffer)?;
+            let rendered = RgbaImage::from_raw(self.viewport.width, self.viewport.height, buffer)
+                .ok_or(SpaceThumbnailsError::ReadbackFailed)?;
+            let image = match &self.options.auto_crop {
+                Some(auto_crop) => auto_crop.apply(&rendered, width, height),
+                None => resample(&rendered, width, height),
+            };
+            output_memory[..byte_count].copy_from_slice(&image);
         }
 
+        let fitted = match self.fitted_backdrop.take() {
+            Some((size, backdrop)) if size == (width, height) => backdrop,
+            _ => self.backdrop.fit(width, height),
+        };
+        fitted.composite(&mut output_memory[..byte_count], width, height);
+        self.fitted_backdrop = Some(((width, height), fitted));
+
+        Ok(byte_count)
+    }
+
+    fn render_into(&mut self, output_memory: &mut [u8]) -> Result<()> {
         unsafe {
             let ok: Rc<Cell<bool>> = Rc::new(Cell::new(false));
             let ok_inner = ok.clone();
@@ -347,23 +1049,501 @@ impl SpaceThumbnailsRenderer {
             self.renderer.end_frame();
             self.engine.flush_and_wait();
 
-            if ok.get() == false {
-                panic!("Take screenshot failed");
+            if !ok.get() {
+                return Err(SpaceThumbnailsError::ReadbackFailed);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Renders the asset a few times, moving the exposure until the average luminance of the
+    /// model reaches middle grey.
+    fn meter_exposure(&mut self, compensation: f32) -> Result<()> {
+        let mut ev100 = Exposure::default().ev100().unwrap_or_default();
+        let mut buffer = vec![0; self.get_render_size_in_byte()];
+
+        for _ in 0..3 {
+            self.metered_ev100 = Some(ev100);
+            self.update_exposure()?;
+            self.render_into(&mut buffer)?;
+            match average_luminance(&buffer) {
+                Some(luminance) => ev100 = metered_ev100(ev100, luminance, compensation),
+                None => break,
+            }
+        }
+
+        self.metered_ev100 = Some(ev100);
+        self.update_exposure()
+    }
+
+    pub fn set_exposure(&mut self, exposure: Exposure) -> Result<&mut Self> {
+        self.options.exposure = exposure;
+        self.metered_ev100 = None;
+        self.update_exposure()?;
+        Ok(self)
+    }
+
+    pub fn get_exposure(&self) -> Exposure {
+        self.options.exposure
+    }
+
+    fn update_exposure(&mut self) -> Result<()> {
+        let (aperture, shutter_speed, sensitivity) = match self.options.exposure {
+            Exposure::Physical {
+                aperture,
+                shutter_speed,
+                sensitivity,
+            } => (aperture, shutter_speed, sensitivity),
+            exposure => {
+                let ev100 = exposure
+                    .ev100()
+                    .or(self.metered_ev100)
+                    .or_else(|| Exposure::default().ev100())
+                    .unwrap_or_default();
+                // with f/1 at ISO 100 the exposure value is the log2 of the inverse shutter speed
+                (1.0, (-ev100).exp2(), 100.0)
+            }
+        };
+
+        unsafe {
+            self.engine
+                .get_camera_component(&self.camera_entity)
+                .ok_or(SpaceThumbnailsError::ResourceCreation("camera"))?
+                .set_exposure_physical(aperture, shutter_speed, sensitivity);
+        }
+        Ok(())
+    }
+
+    pub fn set_tone_mapping(&mut self, tone_mapping: ToneMapping) -> Result<&mut Self> {
+        unsafe {
+            let mut color_grading = ColorGradingBuilder::new()
+                .ok_or(SpaceThumbnailsError::ResourceCreation("color grading"))?
+                .tone_mapping(match tone_mapping {
+                    ToneMapping::Aces => filament::ToneMapping::ACES,
+                    ToneMapping::AcesLegacy => filament::ToneMapping::ACES_LEGACY,
+                    ToneMapping::Filmic => filament::ToneMapping::FILMIC,
+                    ToneMapping::Linear => filament::ToneMapping::LINEAR,
+                })
+                .build(&mut self.engine)
+                .ok_or(SpaceThumbnailsError::ResourceCreation("color grading"))?;
+            self.view.set_color_grading(&mut color_grading);
+            if let Some(mut previous) = self.color_grading.replace(color_grading) {
+                self.engine.destroy_color_grading(&mut previous);
             }
         }
+        self.options.tone_mapping = tone_mapping;
+        Ok(self)
+    }
+
+    pub fn get_tone_mapping(&self) -> ToneMapping {
+        self.options.tone_mapping
+    }
+
+    /// Replaces the image based lighting, the previous environment is kept if the new one can not
+    /// be loaded.
+    pub fn set_environment(&mut self, environment: Environment) -> Result<&mut Self> {
+        unsafe {
+            let mut resources = EnvironmentResources::create(&mut self.engine, &environment)?;
+            self.scene.set_indirect_light(&mut resources.ibl);
+            self.scene.set_skybox(resources.skybox.as_mut());
+            let mut previous = std::mem::replace(&mut self.environment_resources, resources);
+            previous.destroy(&mut self.engine);
+        }
+        self.options.environment = environment;
+        Ok(self)
+    }
+
+    pub fn get_environment(&self) -> &Environment {
+        &self.options.environment
+    }
+
+    /// Replaces the direct lights of the scene, the previous lights are kept if the new ones can
+    /// not be created.
+    pub fn set_lighting(&mut self, lighting: LightingSpec) -> Result<&mut Self> {
+        unsafe {
+            let entities = create_lights(&mut self.engine, &mut self.scene, &lighting)?;
+            let previous = std::mem::replace(&mut self.light_entities, entities);
+            destroy_lights(&mut self.engine, &mut self.scene, previous);
+        }
+        self.options.lighting = lighting;
+        Ok(self)
+    }
+
+    pub fn get_lighting(&self) -> &LightingSpec {
+        &self.options.lighting
+    }
 
-        byte_count
+    /// Fails if the backdrop of `Background::ImageFile` can not be loaded.
+    pub fn set_background(&mut self, background: Background) -> Result<&mut Self> {
+        self.backdrop = background.load()?;
+        self.fitted_backdrop = None;
+        self.options.background = background;
+        Ok(self)
+    }
+
+    pub fn get_background(&self) -> &Background {
+        &self.options.background
+    }
+
+    /// Renders the current view into a new image.
+    pub fn take_screenshot_image(&mut self) -> Result<RgbaImage> {
+        let mut buffer = vec![0; self.get_screenshot_size_in_byte()];
+        self.take_screenshot_sync(&mut buffer)?;
+        let (width, height) = self.get_size();
+        RgbaImage::from_raw(width, height, buffer).ok_or(SpaceThumbnailsError::ReadbackFailed)
+    }
+
+    /// Renders the loaded asset from every view in `options` and lays the results out in a grid.
+    pub fn render_contact_sheet(&mut self, options: &ContactSheetOptions) -> Result<RgbaImage> {
+        let (width, height) = self.get_size();
+        let cell_size = options.cell_size.unwrap_or((width, height));
+        let camera = self.options.camera;
+
+        // rendered at the cell size so the model is framed for the aspect ratio of the cells
+        let cells = self
+            .set_size(cell_size.0, cell_size.1)
+            .and_then(|renderer| {
+                options
+                    .views
+                    .iter()
+                    .map(|view| {
+                        renderer.set_camera(Some(view.camera))?;
+                        renderer.take_screenshot_image()
+                    })
+                    .collect::<Result<Vec<_>>>()
+            });
+
+        self.set_camera(camera)?;
+        self.set_size(width, height)?;
+        Ok(options.compose(cells?, cell_size))
+    }
+
+    /// Renders one image per entry of `sizes`, in the same order. Each size is the longest edge of
+    /// the image, the aspect ratio of the current size is kept and the size is restored afterwards.
+    pub fn render_sizes(
+        &mut self,
+        sizes: &[u32],
+        strategy: SizeStrategy,
+    ) -> Result<Vec<RgbaImage>> {
+        let (width, height) = self.get_size();
+        let images = match strategy {
+            SizeStrategy::Native => sizes
+                .iter()
+                .map(|&size| {
+                    let (w, h) = fit_size(size, width, height);
+                    self.set_size(w, h)?;
+                    self.take_screenshot_image()
+                })
+                .collect::<Result<Vec<_>>>(),
+            SizeStrategy::Downsample => match sizes.iter().max() {
+                Some(&largest) => {
+                    let (w, h) = fit_size(largest, width, height);
+                    self.set_size(w, h)
+                        .and_then(|renderer| renderer.take_screenshot_image())
+                        .map(|master| {
+                            sizes
+                                .iter()
+                                .map(|&size| {
+                                    let (w, h) = fit_size(size, width, height);
+                                    resample(&master, w, h)
+                                })
+                                .collect()
+                        })
+                }
+                None => Ok(Vec::new()),
+            },
+        };
+
+        self.set_size(width, height)?;
+        images
+    }
+
+    /// Orbits the camera around the loaded asset and renders one frame per step.
+    pub fn render_turntable(&mut self, options: &TurntableOptions) -> Result<Vec<AnimationFrame>> {
+        let camera = self.options.camera;
+        let framing = self.options.framing;
+        let orbit = |index: u32, distance: Option<f32>| CameraSpec::Orbit {
+            azimuth: options.start_azimuth + index as f32 * options.degrees_per_frame,
+            elevation: options.elevation,
+            distance,
+        };
+
+        // frame the model once for the whole orbit so it does not change size between frames
+        let distance = match (self.asset_bounds, self.options.projection) {
+            (Some(bounds), ProjectionMode::Perspective) => {
+                let aspect = self.viewport.width as f32 / self.viewport.height as f32;
+                (0..options.frames)
+                    .map(|index| {
+                        let pose = orbit(index, None).resolve(
+                            &bounds,
+                            &framing,
+                            aspect,
+                            lens_vertical_fov(FOCAL_LENGTH),
+                        );
+                        length(sub(pose.eye, pose.target))
+                    })
+                    .reduce(f32::max)
+            }
+            _ => {
+                self.options.framing.mode = FramingMode::BoundingSphere;
+                None
+            }
+        };
+
+        let frames = (0..options.frames)
+            .map(|index| {
+                self.set_camera(Some(orbit(index, distance)))?;
+                Ok(AnimationFrame {
+                    image: self.take_screenshot_image()?,
+                    delay_ms: options.frame_delay_ms,
+                })
+            })
+            .collect::<Result<Vec<_>>>();
+
+        self.options.framing = framing;
+        self.set_camera(camera)?;
+        frames
+    }
+
+    /// Plays an animation of the loaded asset and renders it from a fixed camera. The asset is
+    /// left in the pose of the last frame, unless a pose is set with `set_animation_pose`.
+    pub fn render_animation(&mut self, options: &PlaybackOptions) -> Result<Vec<AnimationFrame>> {
+        let bounds = self.asset_bounds;
+        let frames = self.play_animation(options);
+
+        self.asset_bounds = bounds;
+        self.update_animation()?;
+        self.update_camera()?;
+        frames
+    }
+
+    fn play_animation(&mut self, options: &PlaybackOptions) -> Result<Vec<AnimationFrame>> {
+        let pose_at = |time: f32| AnimationPose {
+            animation: options.animation.clone(),
+            time: AnimationTime::Seconds(time),
+        };
+        let duration = self.apply_pose(&pose_at(0.0))?;
+        let (times, delay_ms) = options.sample_times(duration);
+
+        // frame the union of the bounds over all the frames so nothing leaves the viewport
+        // of the sampled poses, the rest pose may never be shown
+        let mut bounds: Option<Bounds> = None;
+        for &time in &times {
+            self.apply_pose(&pose_at(time))?;
+            bounds = match (bounds, self.posed_bounds()?) {
+                (Some(bounds), Some(posed)) => Some(bounds.union(&posed)),
+                (bounds, posed) => bounds.or(posed),
+            };
+        }
+        self.asset_bounds = bounds.or(self.asset_bounds);
+        self.update_camera()?;
+
+        times
+            .into_iter()
+            .map(|time| {
+                self.apply_pose(&pose_at(time))?;
+                Ok(AnimationFrame {
+                    image: self.take_screenshot_image()?,
+                    delay_ms,
+                })
+            })
+            .collect()
+    }
+
+    /// Resizes the rendered image, the loaded asset is kept and framed again for the new aspect
+    /// ratio.
+    pub fn set_size(&mut self, width: u32, height: u32) -> Result<&mut Self> {
+        self.options.width = width;
+        self.options.height = height;
+        self.update_viewport()?;
+        Ok(self)
     }
 
     pub fn get_size(&self) -> (u32, u32) {
-        (self.viewport.width, self.viewport.height)
+        (self.options.width, self.options.height)
     }
 
     pub fn get_screenshot_size_in_byte(&self) -> usize {
+        (self.options.width * self.options.height * 4) as usize
+    }
+
+    /// Size of a frame read back from the swap chain, larger than the screenshot when
+    /// supersampling.
+    fn get_render_size_in_byte(&self) -> usize {
         (self.viewport.width * self.viewport.height * 4) as usize
     }
 
+    /// Recreates the swap chain and viewport for the current size and supersampling factor.
+    fn update_viewport(&mut self) -> Result<()> {
+        let factor = self.options.quality.supersampling_factor();
+        let (width, height) = (self.options.width * factor, self.options.height * factor);
+        if (self.viewport.width, self.viewport.height) != (width, height) {
+            unsafe {
+                let swap_chain = self
+                    .engine
+                    .create_headless_swap_chain(width, height, SwapChainConfig::TRANSPARENT)
+                    .ok_or(SpaceThumbnailsError::ResourceCreation("swap chain"))?;
+                let mut previous = std::mem::replace(&mut self.swap_chain, swap_chain);
+                self.engine.destroy_swap_chain(&mut previous);
+                self.viewport = Viewport {
+                    left: 0,
+                    bottom: 0,
+                    width,
+                    height,
+                };
+                self.view.set_viewport(&self.viewport);
+            }
+        }
+        self.update_camera()
+    }
+
+    pub fn set_quality(&mut self, quality: Quality) -> Result<&mut Self> {
+        unsafe {
+            self.view
+                .set_multi_sample_anti_aliasing_options(&MultiSampleAntiAliasingOptions {
+                    enabled: quality.msaa_samples > 1,
+                    sample_count: quality.msaa_samples.max(1),
+                    custom_resolve: false,
+                });
+            self.view.set_anti_aliasing(if quality.fxaa {
+                AntiAliasing::FXAA
+            } else {
+                AntiAliasing::NONE
+            });
+        }
+        self.options.quality = quality;
+        self.update_viewport()?;
+        Ok(self)
+    }
+
+    pub fn get_quality(&self) -> Quality {
+        self.options.quality
+    }
+
+    /// Crops every screenshot to the rendered model before the background is composited.
+    pub fn set_auto_crop(&mut self, auto_crop: Option<AutoCrop>) -> &mut Self {
+        self.options.auto_crop = auto_crop;
+        self
+    }
+
+    pub fn get_auto_crop(&self) -> Option<AutoCrop> {
+        self.options.auto_crop
+    }
+
+    /// Poses the loaded asset and the assets loaded later with a frame of one of their
+    /// animations, the camera frames the posed model. Only glTF animations are supported, the
+    /// bindings do not expose the animations of assimp assets, and models without animations keep
+    /// their rest pose. Clearing the pose takes effect for the next loaded asset, the rest pose of
+    /// the current one can not be restored.
+    pub fn set_animation_pose(&mut self, pose: Option<AnimationPose>) -> Result<&mut Self> {
+        self.options.animation = pose;
+        self.update_animation()?;
+        self.update_camera()?;
+        Ok(self)
+    }
+
+    pub fn get_animation_pose(&self) -> Option<&AnimationPose> {
+        self.options.animation.as_ref()
+    }
+
+    fn update_animation(&mut self) -> Result<()> {
+        if let Some(pose) = self.options.animation.clone() {
+            if self.asset_bounds.is_some() {
+                if let Some((index, time)) = pose.select(&self.animations())? {
+                    self.seek_animation(index, time);
+                    // frame the pose instead of the rest pose
+                    self.asset_bounds = self.posed_bounds()?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies `pose` to the loaded asset and returns the duration of the selected animation.
+    fn apply_pose(&mut self, pose: &AnimationPose) -> Result<f32> {
+        let animations = self.animations();
+        let (index, time) = pose
+            .resolve(&animations)
+            .ok_or_else(|| SpaceThumbnailsError::AnimationNotFound(pose.animation.clone()))?;
+        self.seek_animation(index, time);
+        Ok(animations[index].1)
+    }
+
+    /// Name and duration of every animation of the loaded asset.
+    fn animations(&self) -> Vec<(Option<String>, f32)> {
+        match &self.animator {
+            Some(AssetAnimator { animator, .. }) => unsafe {
+                (0..animator.get_animation_count())
+                    .map(|index| {
+                        (
+                            animator.get_animation_name(index),
+                            animator.get_animation_duration(index),
+                        )
+                    })
+                    .collect()
+            },
+            None => Vec::new(),
+        }
+    }
+
+    fn seek_animation(&mut self, index: usize, time: f32) {
+        if let Some(AssetAnimator { animator, .. }) = &mut self.animator {
+            unsafe {
+                animator.apply_animation(index, time);
+                animator.update_bone_matrices();
+            }
+        }
+    }
+
+    /// Bounds of the asset in its current pose, in the normalized model space. The boxes of
+    /// skinned renderables stay in the bind pose, so the positions of the joints are included.
+    fn posed_bounds(&mut self) -> Result<Option<Bounds>> {
+        match &self.animator {
+            Some(animator) => unsafe {
+                let renderables = renderables_bounds(&mut self.engine, &animator.renderables)?;
+                let joints = joints_bounds(&mut self.engine, &animator.joints)?;
+                Ok(match (renderables, joints) {
+                    (Some(renderables), Some(joints)) => Some(renderables.union(&joints)),
+                    (renderables, joints) => renderables.or(joints).or(self.asset_bounds),
+                })
+            },
+            None => Ok(self.asset_bounds),
+        }
+    }
+
+    /// Selects the glTF scene added by the next loaded asset, `None` uses the default scene.
+    pub fn set_scene(&mut self, scene: Option<SceneSelector>) -> &mut Self {
+        self.options.scene = scene;
+        self
+    }
+
+    pub fn get_scene(&self) -> Option<&SceneSelector> {
+        self.options.scene.as_ref()
+    }
+
+    /// Selects the `KHR_materials_variants` variant applied to the next loaded asset, `None`
+    /// keeps the default materials.
+    pub fn set_material_variant(&mut self, variant: Option<String>) -> &mut Self {
+        self.options.material_variant = variant;
+        self
+    }
+
+    pub fn get_material_variant(&self) -> Option<&str> {
+        self.options.material_variant.as_deref()
+    }
+
+    /// Statistics of the loaded asset, `None` when no asset is loaded.
+    pub fn get_model_info(&self) -> Option<&ModelInfo> {
+        self.model_info.as_ref()
+    }
+
     pub fn destory_opened_asset(&mut self) -> &mut Self {
+        self.animator = None;
+        self.asset_bounds = None;
+        self.model_info = None;
+        self.asset_cameras.clear();
+        self.metered_ev100 = None;
         let destory_asset = self.destory_asset.take();
         if let Some(destory) = destory_asset {
             destory(&mut self.engine, &mut self.scene)
@@ -379,11 +1559,16 @@ impl Drop for SpaceThumbnailsRenderer {
             self.destory_opened_asset();
             let mut entity_manager = self.engine.get_entity_manager().unwrap();
             self.engine.destroy_entity_components(&self.camera_entity);
-            self.engine.destroy_entity_components(&self.sunlight_entity);
             entity_manager.destory(&mut self.camera_entity);
-            entity_manager.destory(&mut self.sunlight_entity);
-            self.engine.destroy_texture(&mut self.ibl_texture);
-            self.engine.destroy_indirect_light(&mut self.ibl);
+            destroy_lights(
+                &mut self.engine,
+                &mut self.scene,
+                std::mem::take(&mut self.light_entities),
+            );
+            self.environment_resources.destroy(&mut self.engine);
+            if let Some(mut color_grading) = self.color_grading.take() {
+                self.engine.destroy_color_grading(&mut color_grading);
+            }
             self.engine.destroy_scene(&mut self.scene);
             self.engine.destroy_view(&mut self.view);
             self.engine.destroy_renderer(&mut self.renderer);
@@ -393,27 +1578,205 @@ impl Drop for SpaceThumbnailsRenderer {
     }
 }
 
-unsafe fn setup_camera_surround_view(camera: &mut Camera, aabb: &Aabb, viewport: &Viewport) {
-    let aspect = viewport.width as f64 / viewport.height as f64;
-    let half_extent = aabb.extent();
-    camera.set_lens_projection(28.0, aspect, 0.01, f64::INFINITY);
-    camera.look_at_up(
-        &(aabb.center()
-            + Float3::from(((half_extent[0] + half_extent[2]) / 2.0).max(half_extent[1]))
-                * Float3::from([2.5, 1.7, 2.5])),
-        &aabb.center(),
-        &[0.0, 1.0, 0.0].into(),
-    );
+/// Resources of a renderer being created, the ones already created are destroyed with their
+/// engine when the creation fails half way.
+struct RendererParts {
+    engine: Option<Engine>,
+    scene: Option<Scene>,
+    swap_chain: Option<SwapChain>,
+    renderer: Option<Renderer>,
+    view: Option<View>,
+    camera_entity: Option<Entity>,
+    environment_resources: Option<EnvironmentResources>,
+    light_entities: Vec<Entity>,
+}
+
+impl Drop for RendererParts {
+    fn drop(&mut self) {
+        let engine = match &mut self.engine {
+            Some(engine) => engine,
+            None => return,
+        };
+        unsafe {
+            if let Some(scene) = &mut self.scene {
+                destroy_lights(engine, scene, std::mem::take(&mut self.light_entities));
+            }
+            if let Some(mut environment_resources) = self.environment_resources.take() {
+                environment_resources.destroy(engine);
+            }
+            if let Some(mut camera_entity) = self.camera_entity.take() {
+                engine.destroy_entity_components(&camera_entity);
+                if let Some(mut entity_manager) = engine.get_entity_manager() {
+                    entity_manager.destory(&mut camera_entity);
+                }
+            }
+            if let Some(mut view) = self.view.take() {
+                engine.destroy_view(&mut view);
+            }
+            if let Some(mut renderer) = self.renderer.take() {
+                engine.destroy_renderer(&mut renderer);
+            }
+            if let Some(mut swap_chain) = self.swap_chain.take() {
+                engine.destroy_swap_chain(&mut swap_chain);
+            }
+            if let Some(mut scene) = self.scene.take() {
+                engine.destroy_scene(&mut scene);
+            }
+            Engine::destroy(engine);
+        }
+    }
 }
 
-fn fit_into_unit_cube(bounds: &Aabb) -> Mat4f {
-    let min = bounds.min;
-    let max = bounds.max;
-    let max_extent = f32::max(f32::max(max[0] - min[0], max[1] - min[1]), max[2] - min[2]);
-    let scale_factor = 2.0 / max_extent;
-    let center = (min + max) / 2.0;
+fn fit_into_unit_cube(bounds: &Bounds) -> Mat4f {
+    let scale_factor = unit_cube_scale(bounds);
     Mat4f::scaling(Float3::new(scale_factor, scale_factor, scale_factor))
-        * Mat4f::translation(center * -1.0)
+        * Mat4f::translation(Float3::from(scale(bounds.center(), -1.0)))
+}
+
+/// `bounds` after `fit_into_unit_cube`.
+fn normalize_bounds(bounds: &Bounds) -> Bounds {
+    let scale_factor = unit_cube_scale(bounds);
+    let center = bounds.center();
+    Bounds {
+        min: scale(sub(bounds.min, center), scale_factor),
+        max: scale(sub(bounds.max, center), scale_factor),
+    }
+}
+
+fn unit_cube_scale(bounds: &Bounds) -> f32 {
+    let [x, y, z] = scale(bounds.half_extent(), 2.0);
+    2.0 / x.max(y).max(z)
+}
+
+/// Cameras placed in `scene` of `document`, moved into the normalized model space of an asset
+/// with the given bounds.
+fn gltf_cameras(document: &Document, scene: Option<usize>, bounds: &Bounds) -> Vec<AssetCamera> {
+    let camera_names = names(&document.cameras, "camera");
+    let scale_factor = unit_cube_scale(bounds);
+    let center = bounds.center();
+    document
+        .camera_nodes(scene)
+        .into_iter()
+        .filter_map(|node| {
+            let camera = document.cameras.get(node.camera)?;
+            let (fov, half_extent, near) = match (&camera.perspective, &camera.orthographic) {
+                (Some(perspective), _) => (
+                    Some(perspective.yfov.to_degrees() as f64),
+                    0.0,
+                    perspective.znear,
+                ),
+                (None, Some(orthographic)) => (None, orthographic.ymag, orthographic.znear),
+                (None, None) => return None,
+            };
+            let eye = scale(sub(node.eye, center), scale_factor);
+            Some(AssetCamera {
+                index: node.camera,
+                name: camera_names[node.camera].clone(),
+                fov,
+                half_extent: (half_extent * scale_factor) as f64,
+                vertical: true,
+                near: ((near * scale_factor) as f64).max(0.001),
+                transform: Mat4f::look_at(
+                    &eye.into(),
+                    &add(eye, node.forward).into(),
+                    &node.up.into(),
+                ),
+            })
+        })
+        .collect()
+}
+
+/// World space bounds of the renderables among `entities`, skinned ones in their bind pose.
+unsafe fn renderables_bounds(engine: &mut Engine, entities: &[Entity]) -> Result<Option<Bounds>> {
+    let mut renderable_manager = engine
+        .get_renderable_manager()
+        .ok_or(SpaceThumbnailsError::ResourceCreation("renderable manager"))?;
+    let mut transform_manager = engine
+        .get_transform_manager()
+        .ok_or(SpaceThumbnailsError::ResourceCreation("transform manager"))?;
+    let mut bounds: Option<Bounds> = None;
+    for entity in entities {
+        let (renderable, transform) = match (
+            renderable_manager.get_instance(entity),
+            transform_manager.get_instance(entity),
+        ) {
+            (Some(renderable), Some(transform)) => (renderable, transform),
+            _ => continue,
+        };
+        let aabb = renderable_manager
+            .get_axis_aligned_bounding_box(&renderable)
+            .transform(transform_manager.get_world_transform_float(&transform));
+        let entity_bounds = bounds_from_aabb(&aabb);
+        bounds = Some(match bounds {
+            Some(bounds) => bounds.union(&entity_bounds),
+            None => entity_bounds,
+        });
+    }
+    Ok(bounds)
+}
+
+/// World space bounds of the positions of `joints`.
+unsafe fn joints_bounds(engine: &mut Engine, joints: &[Entity]) -> Result<Option<Bounds>> {
+    let mut transform_manager = engine
+        .get_transform_manager()
+        .ok_or(SpaceThumbnailsError::ResourceCreation("transform manager"))?;
+    let origin = Aabb {
+        min: Float3::new(0.0, 0.0, 0.0),
+        max: Float3::new(0.0, 0.0, 0.0),
+    };
+    let mut bounds: Option<Bounds> = None;
+    for joint in joints {
+        let transform = match transform_manager.get_instance(joint) {
+            Some(transform) => transform,
+            None => continue,
+        };
+        let position = bounds_from_aabb(
+            &origin.transform(transform_manager.get_world_transform_float(&transform)),
+        );
+        bounds = Some(match bounds {
+            Some(bounds) => bounds.union(&position),
+            None => position,
+        });
+    }
+    Ok(bounds)
+}
+
+fn bounds_from_aabb(aabb: &Aabb) -> Bounds {
+    Bounds {
+        min: [aabb.min[0], aabb.min[1], aabb.min[2]],
+        max: [aabb.max[0], aabb.max[1], aabb.max[2]],
+    }
+}
+
+fn file_extension(path: &Path) -> Result<String> {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .ok_or_else(|| SpaceThumbnailsError::InvalidFilename(path.to_owned()))
+}
+
+/// The loader extension of a model: the detected format, else the given extension.
+fn resolve_format(detected: Option<ModelFormat>, extension: Option<&str>) -> Result<String> {
+    match (detected, extension) {
+        (Some(format), _) => Ok(format.extension().to_owned()),
+        (None, Some(extension)) => Ok(extension.trim_start_matches('.').to_ascii_lowercase()),
+        (None, None) => Err(SpaceThumbnailsError::UnsupportedFormat(
+            "unknown".to_owned(),
+        )),
+    }
+}
+
+fn assimp_error(err: impl ToString, extension: &str) -> SpaceThumbnailsError {
+    let message = err.to_string();
+    // assimp has no importer for this kind of file
+    if message.contains("No suitable reader found") {
+        SpaceThumbnailsError::UnsupportedFormat(extension.to_owned())
+    } else {
+        SpaceThumbnailsError::CorruptAsset {
+            format: extension.to_owned(),
+            source: message.into(),
+        }
+    }
 }
 
 fn is_base64_data_uri(uri: &str) -> bool {
@@ -426,7 +1789,32 @@ mod test {
 
     use image::{ImageBuffer, ImageOutputFormat, Rgba};
 
-    use crate::{RendererBackend, SpaceThumbnailsRenderer};
+    use crate::{RendererBackend, SpaceThumbnailsError, SpaceThumbnailsRenderer};
+
+    use super::assimp_error;
+
+    #[test]
+    fn assimp_unsupported_format() {
+        let err = assimp_error(
+            "No suitable reader found for the file format of file \"$$$___magic___$$$.xyz\".",
+            "xyz",
+        );
+        assert!(matches!(err, SpaceThumbnailsError::UnsupportedFormat(format) if format == "xyz"));
+        let err = assimp_error("OBJ: unexpected end of file", "obj");
+        assert!(
+            matches!(err, SpaceThumbnailsError::CorruptAsset { format, .. } if format == "obj")
+        );
+    }
+
+    #[test]
+    fn load_misnamed_gltf_json() {
+        let mut renderer = SpaceThumbnailsRenderer::new(RendererBackend::Vulkan, 64, 64).unwrap();
+        let document = br#"{"asset": {"version": "2.0"}, "scene": 0, "scenes": [{"nodes": []}]}"#;
+        renderer
+            .load_asset_from_memory(document, "model.glb")
+            .unwrap();
+        assert_eq!(renderer.get_model_info().unwrap().format, "gltf");
+    }
 
     #[test]
     fn render_file_test() {
@@ -450,7 +1838,8 @@ mod test {
             let filename = filepath.file_name().unwrap().to_str().unwrap();
 
             let now = Instant::now();
-            let mut renderer = SpaceThumbnailsRenderer::new(RendererBackend::Vulkan, 800, 800);
+            let mut renderer =
+                SpaceThumbnailsRenderer::new(RendererBackend::Vulkan, 800, 800).unwrap();
             let elapsed = now.elapsed();
             println!("Initialize renderer, Elapsed: {:.2?}", elapsed);
 
@@ -462,7 +1851,9 @@ mod test {
             let mut screenshot_buffer = vec![0; renderer.get_screenshot_size_in_byte()];
 
             let now = Instant::now();
-            renderer.take_screenshot_sync(screenshot_buffer.as_mut_slice());
+            renderer
+                .take_screenshot_sync(screenshot_buffer.as_mut_slice())
+                .unwrap();
             let elapsed = now.elapsed();
             println!("Render and take screenshot, Elapsed: {:.2?}", elapsed);
 
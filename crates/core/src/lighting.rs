@@ -0,0 +1,252 @@
+use serde::{Deserialize, Serialize};
+
+use filament_bindings::{
+    filament::{sRGBColor, Engine, LightBuilder, LightType, Scene},
+    math::Float3,
+    utils::Entity,
+};
+
+use crate::{Result, SpaceThumbnailsError};
+
+/// The sun, a directional light with a visible disk and halo when a skybox is shown.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SunLight {
+    /// sRGB color.
+    pub color: [f32; 3],
+    /// Illuminance in lux.
+    pub intensity: f32,
+    /// Direction the light travels, in the normalized model space.
+    pub direction: [f32; 3],
+    pub angular_radius: f32,
+    pub halo_size: f32,
+    pub halo_falloff: f32,
+}
+
+impl Default for SunLight {
+    fn default() -> Self {
+        Self {
+            color: [0.98, 0.92, 0.89],
+            intensity: 100000.0,
+            direction: [0.6, -1.0, -0.8],
+            angular_radius: 1.0,
+            halo_size: 2.0,
+            halo_falloff: 80.0,
+        }
+    }
+}
+
+/// Positions and directions are in the normalized model space, where the model fits in the
+/// `[-1, 1]` cube.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LightKind {
+    Directional {
+        direction: [f32; 3],
+    },
+    Point {
+        position: [f32; 3],
+        /// Distance at which the light has no effect anymore.
+        falloff: f32,
+    },
+    Spot {
+        position: [f32; 3],
+        direction: [f32; 3],
+        falloff: f32,
+        /// Half angles of the cone in degrees.
+        inner_cone: f32,
+        outer_cone: f32,
+    },
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Light {
+    pub kind: LightKind,
+    /// sRGB color.
+    pub color: [f32; 3],
+    /// Illuminance in lux for directional lights, luminous power in lumens otherwise.
+    pub intensity: f32,
+    /// Whether the light casts shadows, only honored when shadows are enabled on the rig.
+    #[serde(default)]
+    pub cast_shadows: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LightingPreset {
+    /// A single warm sun from the top right.
+    Sun,
+    /// Key, fill and rim lights.
+    ThreePoint,
+    /// No direct lights, only the environment lighting.
+    EnvironmentOnly,
+}
+
+/// The direct lights of the scene, on top of the image based lighting of the environment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LightingSpec {
+    pub sun: Option<SunLight>,
+    pub lights: Vec<Light>,
+    /// Shadows darken small parts a lot, they can be turned off for the whole rig.
+    pub shadows: bool,
+}
+
+impl Default for LightingSpec {
+    fn default() -> Self {
+        Self::preset(LightingPreset::Sun)
+    }
+}
+
+impl LightingSpec {
+    pub fn preset(preset: LightingPreset) -> Self {
+        match preset {
+            LightingPreset::Sun => Self {
+                sun: Some(SunLight::default()),
+                lights: Vec::new(),
+                shadows: true,
+            },
+            LightingPreset::ThreePoint => Self {
+                sun: None,
+                lights: vec![
+                    // key, front right and above the model
+                    Light {
+                        kind: LightKind::Directional {
+                            direction: [-0.6, -0.7, -0.8],
+                        },
+                        color: [1.0, 0.96, 0.9],
+                        intensity: 90000.0,
+                        cast_shadows: true,
+                    },
+                    // fill, front left at the model height
+                    Light {
+                        kind: LightKind::Directional {
+                            direction: [0.8, -0.2, -0.6],
+                        },
+                        color: [0.85, 0.9, 1.0],
+                        intensity: 35000.0,
+                        cast_shadows: false,
+                    },
+                    // rim, behind and above the model
+                    Light {
+                        kind: LightKind::Directional {
+                            direction: [0.0, -0.6, 1.0],
+                        },
+                        color: [1.0, 1.0, 1.0],
+                        intensity: 70000.0,
+                        cast_shadows: false,
+                    },
+                ],
+                shadows: true,
+            },
+            LightingPreset::EnvironmentOnly => Self {
+                sun: None,
+                lights: Vec::new(),
+                shadows: false,
+            },
+        }
+    }
+}
+
+/// Creates the light entities of `spec` and adds them to `scene`. The lights are only added once
+/// all of them are built, on failure the created entities are destroyed and `scene` is unchanged.
+pub(crate) unsafe fn create_lights(
+    engine: &mut Engine,
+    scene: &mut Scene,
+    spec: &LightingSpec,
+) -> Result<Vec<Entity>> {
+    let mut entities = Vec::new();
+    match build_lights(engine, spec, &mut entities) {
+        Ok(()) => {
+            for entity in &entities {
+                scene.add_entity(entity);
+            }
+            Ok(entities)
+        }
+        Err(err) => {
+            destroy_lights(engine, scene, entities);
+            Err(err)
+        }
+    }
+}
+
+/// Pushes every entity to `entities` as soon as it is created, so a failed light can be destroyed
+/// along with the others.
+unsafe fn build_lights(
+    engine: &mut Engine,
+    spec: &LightingSpec,
+    entities: &mut Vec<Entity>,
+) -> Result<()> {
+    let mut entity_manager = engine
+        .get_entity_manager()
+        .ok_or(SpaceThumbnailsError::ResourceCreation("entity manager"))?;
+
+    if let Some(sun) = &spec.sun {
+        let entity = entity_manager.create();
+        entities.push(entity);
+        LightBuilder::new(LightType::SUN)
+            .ok_or(SpaceThumbnailsError::ResourceCreation("sun light"))?
+            .color(&sRGBColor(Float3::from(sun.color)).to_linear_fast())
+            .intensity(sun.intensity)
+            .direction(&Float3::from(sun.direction).normalize())
+            .cast_shadows(spec.shadows)
+            .sun_angular_radius(sun.angular_radius)
+            .sun_halo_size(sun.halo_size)
+            .sun_halo_falloff(sun.halo_falloff)
+            .build(engine, &entity)
+            .ok_or(SpaceThumbnailsError::ResourceCreation("sun light"))?;
+    }
+
+    for light in &spec.lights {
+        let entity = entity_manager.create();
+        entities.push(entity);
+        let light_type = match light.kind {
+            LightKind::Directional { .. } => LightType::DIRECTIONAL,
+            LightKind::Point { .. } => LightType::POINT,
+            LightKind::Spot { .. } => LightType::SPOT,
+        };
+        let mut builder = LightBuilder::new(light_type)
+            .ok_or(SpaceThumbnailsError::ResourceCreation("light"))?
+            .color(&sRGBColor(Float3::from(light.color)).to_linear_fast())
+            .intensity(light.intensity)
+            .cast_shadows(spec.shadows && light.cast_shadows);
+        builder = match light.kind {
+            LightKind::Directional { direction } => {
+                builder.direction(&Float3::from(direction).normalize())
+            }
+            LightKind::Point { position, falloff } => {
+                builder.position(&Float3::from(position)).falloff(falloff)
+            }
+            LightKind::Spot {
+                position,
+                direction,
+                falloff,
+                inner_cone,
+                outer_cone,
+            } => builder
+                .position(&Float3::from(position))
+                .direction(&Float3::from(direction).normalize())
+                .falloff(falloff)
+                .spot_light_cone(inner_cone.to_radians(), outer_cone.to_radians()),
+        };
+        builder
+            .build(engine, &entity)
+            .ok_or(SpaceThumbnailsError::ResourceCreation("light"))?;
+    }
+
+    Ok(())
+}
+
+/// Removes lights created by `create_lights` from `scene` and destroys them.
+pub(crate) unsafe fn destroy_lights(engine: &mut Engine, scene: &mut Scene, entities: Vec<Entity>) {
+    let mut entity_manager = match engine.get_entity_manager() {
+        Some(entity_manager) => entity_manager,
+        None => return,
+    };
+    for mut entity in entities {
+        scene.remove_entity(&entity);
+        engine.destroy_entity_components(&entity);
+        entity_manager.destory(&mut entity);
+    }
+}
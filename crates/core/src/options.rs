@@ -0,0 +1,239 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AnimationPose, AutoCrop, Background, CameraSpec, EmbeddedCamera, Environment, Exposure,
+    Framing, LightingSpec, ProjectionMode, Quality, RendererBackend, SceneSelector, ToneMapping,
+};
+
+/// Everything that affects the look of a thumbnail, shared by the command line tool and the
+/// Windows thumbnail providers. Can be read from TOML or JSON, missing fields keep their default
+/// and unknown fields are an error.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RenderOptions {
+    pub backend: RendererBackend,
+    pub width: u32,
+    pub height: u32,
+    /// `None` uses the camera stored in the file if there is one, otherwise a three-quarter view.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub camera: Option<CameraSpec>,
+    /// Camera stored in the file used when `camera` is `None`.
+    pub embedded_camera: EmbeddedCamera,
+    pub framing: Framing,
+    pub projection: ProjectionMode,
+    /// `Background::Image` can not be serialized, use `Background::ImageFile` instead.
+    pub background: Background,
+    pub environment: Environment,
+    pub lighting: LightingSpec,
+    pub exposure: Exposure,
+    pub tone_mapping: ToneMapping,
+    pub quality: Quality,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_crop: Option<AutoCrop>,
+    /// glTF scene to render, `None` uses the default scene.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scene: Option<SceneSelector>,
+    /// Name of the glTF `KHR_materials_variants` variant to apply.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub material_variant: Option<String>,
+    /// `None` renders the rest pose.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub animation: Option<AnimationPose>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            backend: RendererBackend::Default,
+            width: 800,
+            height: 800,
+            camera: None,
+            embedded_camera: EmbeddedCamera::default(),
+            framing: Framing::default(),
+            projection: ProjectionMode::default(),
+            background: Background::default(),
+            environment: Environment::default(),
+            lighting: LightingSpec::default(),
+            exposure: Exposure::default(),
+            tone_mapping: ToneMapping::default(),
+            quality: Quality::default(),
+            auto_crop: None,
+            scene: None,
+            material_variant: None,
+            animation: None,
+        }
+    }
+}
+
+impl RenderOptions {
+    pub fn builder() -> RenderOptionsBuilder {
+        RenderOptionsBuilder::default()
+    }
+}
+
+/// Builds `RenderOptions`, starting from the defaults.
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptionsBuilder {
+    options: RenderOptions,
+}
+
+impl RenderOptionsBuilder {
+    pub fn backend(mut self, backend: RendererBackend) -> Self {
+        self.options.backend = backend;
+        self
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.options.width = width;
+        self.options.height = height;
+        self
+    }
+
+    pub fn camera(mut self, camera: impl Into<CameraSpec>) -> Self {
+        self.options.camera = Some(camera.into());
+        self
+    }
+
+    pub fn embedded_camera(mut self, embedded_camera: EmbeddedCamera) -> Self {
+        self.options.embedded_camera = embedded_camera;
+        self
+    }
+
+    pub fn framing(mut self, framing: Framing) -> Self {
+        self.options.framing = framing;
+        self
+    }
+
+    pub fn projection(mut self, projection: ProjectionMode) -> Self {
+        self.options.projection = projection;
+        self
+    }
+
+    pub fn background(mut self, background: Background) -> Self {
+        self.options.background = background;
+        self
+    }
+
+    pub fn environment(mut self, environment: Environment) -> Self {
+        self.options.environment = environment;
+        self
+    }
+
+    pub fn lighting(mut self, lighting: LightingSpec) -> Self {
+        self.options.lighting = lighting;
+        self
+    }
+
+    pub fn exposure(mut self, exposure: Exposure) -> Self {
+        self.options.exposure = exposure;
+        self
+    }
+
+    pub fn tone_mapping(mut self, tone_mapping: ToneMapping) -> Self {
+        self.options.tone_mapping = tone_mapping;
+        self
+    }
+
+    pub fn quality(mut self, quality: Quality) -> Self {
+        self.options.quality = quality;
+        self
+    }
+
+    pub fn auto_crop(mut self, auto_crop: AutoCrop) -> Self {
+        self.options.auto_crop = Some(auto_crop);
+        self
+    }
+
+    pub fn scene(mut self, scene: SceneSelector) -> Self {
+        self.options.scene = Some(scene);
+        self
+    }
+
+    pub fn material_variant(mut self, variant: impl Into<String>) -> Self {
+        self.options.material_variant = Some(variant.into());
+        self
+    }
+
+    pub fn animation(mut self, animation: AnimationPose) -> Self {
+        self.options.animation = Some(animation);
+        self
+    }
+
+    pub fn build(self) -> RenderOptions {
+        self.options
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        AnimationPose, AnimationSelector, AnimationTime, Background, CameraPreset, CameraSpec,
+        EmbeddedCamera, Exposure, SceneSelector,
+    };
+
+    use super::RenderOptions;
+
+    fn customized() -> Vec<RenderOptions> {
+        let base = RenderOptions::builder()
+            .size(256, 128)
+            .embedded_camera(EmbeddedCamera::Name("main".to_owned()))
+            .background(Background::Gradient {
+                top: [255, 255, 255],
+                bottom: [32, 32, 32],
+            })
+            .exposure(Exposure::Auto { compensation: 1.0 })
+            .scene(SceneSelector::Index(1))
+            .animation(AnimationPose {
+                animation: AnimationSelector::Name("walk".to_owned()),
+                time: AnimationTime::Normalized(0.5),
+            });
+        [
+            CameraSpec::Preset(CameraPreset::Top),
+            CameraSpec::Orbit {
+                azimuth: 30.0,
+                elevation: 15.0,
+                distance: Some(4.0),
+            },
+            CameraSpec::LookAt {
+                eye: [0.0, 1.0, 3.0],
+                target: [0.0, 0.0, 0.0],
+                up: [0.0, 1.0, 0.0],
+            },
+        ]
+        .into_iter()
+        .map(|camera| base.clone().camera(camera).build())
+        .collect()
+    }
+
+    #[test]
+    fn toml_round_trip() {
+        for options in [RenderOptions::default()].into_iter().chain(customized()) {
+            let text = toml::to_string(&options).unwrap();
+            assert_eq!(toml::from_str::<RenderOptions>(&text).unwrap(), options);
+        }
+    }
+
+    #[test]
+    fn json_round_trip() {
+        for options in [RenderOptions::default()].into_iter().chain(customized()) {
+            let text = serde_json::to_string(&options).unwrap();
+            assert_eq!(
+                serde_json::from_str::<RenderOptions>(&text).unwrap(),
+                options
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        assert!(toml::from_str::<RenderOptions>("widht = 256").is_err());
+        assert!(toml::from_str::<RenderOptions>("[framing]\nmargn = 0.1").is_err());
+        assert_eq!(
+            toml::from_str::<RenderOptions>("width = 256\ncamera = \"front\"").unwrap(),
+            RenderOptions::builder()
+                .size(256, 800)
+                .camera(CameraPreset::Front)
+                .build()
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Result, SpaceThumbnailsError};
+
+/// Picks one of the animations stored in the model.
+///
+/// In configuration files an index is written as a number and a name as a string.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AnimationSelector {
+    Index(usize),
+    Name(String),
+}
+
+impl Default for AnimationSelector {
+    fn default() -> Self {
+        Self::Index(0)
+    }
+}
+
+impl fmt::Display for AnimationSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Index(index) => write!(f, "#{}", index),
+            Self::Name(name) => write!(f, "\"{}\"", name),
+        }
+    }
+}
+
+/// Position in the animation, clamped to its duration.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AnimationTime {
+    Seconds(f32),
+    /// `0` is the first frame and `1` the last one.
+    Normalized(f32),
+}
+
+impl Default for AnimationTime {
+    fn default() -> Self {
+        Self::Seconds(0.0)
+    }
+}
+
+/// Poses the model with a frame of one of its animations instead of the rest pose.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnimationPose {
+    pub animation: AnimationSelector,
+    pub time: AnimationTime,
+}
+
+impl AnimationPose {
+    /// Returns the index of the selected animation and the time in seconds, given the name and
+    /// duration of every animation of the model.
+    pub(crate) fn resolve(&self, animations: &[(Option<String>, f32)]) -> Option<(usize, f32)> {
+        let index = match &self.animation {
+            AnimationSelector::Index(index) => *index,
+            AnimationSelector::Name(name) => animations
+                .iter()
+                .position(|(animation, _)| animation.as_deref() == Some(name.as_str()))?,
+        };
+        let duration = animations.get(index)?.1.max(0.0);
+        let time = match self.time {
+            AnimationTime::Seconds(seconds) => seconds,
+            AnimationTime::Normalized(position) => position * duration,
+        };
+        Some((index, time.clamp(0.0, duration)))
+    }
+
+    /// Like `resolve`, but a model without animations is left in its rest pose (`None`) while an
+    /// animation missing from a model that has some is an error.
+    pub(crate) fn select(
+        &self,
+        animations: &[(Option<String>, f32)],
+    ) -> Result<Option<(usize, f32)>> {
+        if animations.is_empty() {
+            return Ok(None);
+        }
+        self.resolve(animations)
+            .map(Some)
+            .ok_or_else(|| SpaceThumbnailsError::AnimationNotFound(self.animation.clone()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::SpaceThumbnailsError;
+
+    use super::{AnimationPose, AnimationSelector, AnimationTime};
+
+    #[test]
+    fn resolve_by_name() {
+        let animations = [
+            (Some("idle".to_owned()), 2.0),
+            (Some("walk".to_owned()), 4.0),
+        ];
+        let pose = AnimationPose {
+            animation: AnimationSelector::Name("walk".to_owned()),
+            time: AnimationTime::Normalized(0.25),
+        };
+        assert_eq!(pose.resolve(&animations), Some((1, 1.0)));
+
+        let pose = AnimationPose {
+            animation: AnimationSelector::Name("run".to_owned()),
+            ..AnimationPose::default()
+        };
+        assert_eq!(pose.resolve(&animations), None);
+    }
+
+    #[test]
+    fn time_is_clamped() {
+        let animations = [(None, 2.0)];
+        let pose = AnimationPose {
+            animation: AnimationSelector::Index(0),
+            time: AnimationTime::Seconds(5.0),
+        };
+        assert_eq!(pose.resolve(&animations), Some((0, 2.0)));
+        assert_eq!(
+            AnimationPose {
+                animation: AnimationSelector::Index(1),
+                ..AnimationPose::default()
+            }
+            .resolve(&animations),
+            None
+        );
+    }
+
+    #[test]
+    fn models_without_animations_are_not_posed() {
+        let pose = AnimationPose {
+            animation: AnimationSelector::Name("walk".to_owned()),
+            ..AnimationPose::default()
+        };
+        assert!(matches!(pose.select(&[]), Ok(None)));
+        assert!(matches!(
+            pose.select(&[(Some("idle".to_owned()), 2.0)]),
+            Err(SpaceThumbnailsError::AnimationNotFound(_))
+        ));
+        assert!(matches!(
+            AnimationPose::default().select(&[(None, 2.0)]),
+            Ok(Some((0, _)))
+        ));
+    }
+}
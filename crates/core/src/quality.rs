@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// Anti-aliasing settings of the renderer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Quality {
+    /// Samples per pixel of multisample anti-aliasing, `1` disables it. Filament clamps the count
+    /// to what the backend supports.
+    pub msaa_samples: u8,
+    /// Fast approximate anti-aliasing as a post-process.
+    pub fxaa: bool,
+    /// Renders at this multiple of the requested size and filters the result down, `1` disables
+    /// supersampling.
+    pub supersampling: u32,
+}
+
+impl Default for Quality {
+    fn default() -> Self {
+        Self {
+            msaa_samples: 1,
+            fxaa: true,
+            supersampling: 1,
+        }
+    }
+}
+
+impl Quality {
+    pub(crate) fn supersampling_factor(&self) -> u32 {
+        self.supersampling.clamp(1, 8)
+    }
+}
@@ -0,0 +1,106 @@
+use image::{imageops, ImageBuffer, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+/// How `render_sizes` produces each requested size.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SizeStrategy {
+    /// Render the largest size once and filter it down to the others.
+    #[default]
+    Downsample,
+    /// Render every size at its own resolution.
+    Native,
+}
+
+/// Size of an image whose longest edge is `size`, keeping the aspect ratio of `width` x `height`.
+pub(crate) fn fit_size(size: u32, width: u32, height: u32) -> (u32, u32) {
+    let size = size.max(1);
+    if width >= height {
+        (
+            size,
+            ((size as u64 * height as u64) / width as u64).max(1) as u32,
+        )
+    } else {
+        (
+            ((size as u64 * width as u64) / height as u64).max(1) as u32,
+            size,
+        )
+    }
+}
+
+/// Lanczos3 resize of a straight alpha image. The color channels are filtered premultiplied so
+/// transparent pixels do not bleed dark fringes into the edges of the model.
+pub(crate) fn resample(image: &RgbaImage, width: u32, height: u32) -> RgbaImage {
+    if image.dimensions() == (width, height) {
+        return image.clone();
+    }
+
+    let premultiplied = ImageBuffer::from_fn(image.width(), image.height(), |x, y| {
+        let [r, g, b, a] = image.get_pixel(x, y).0.map(|c| c as f32 / 255.0);
+        Rgba([r * a, g * a, b * a, a])
+    });
+    let resized = imageops::resize(
+        &premultiplied,
+        width,
+        height,
+        imageops::FilterType::Lanczos3,
+    );
+
+    ImageBuffer::from_fn(width, height, |x, y| {
+        let [r, g, b, a] = resized.get_pixel(x, y).0;
+        let a = a.clamp(0.0, 1.0);
+        let channel = |c: f32| {
+            let c = if a > 0.0 { c / a } else { 0.0 };
+            (c.clamp(0.0, 1.0) * 255.0).round() as u8
+        };
+        Rgba([
+            channel(r),
+            channel(g),
+            channel(b),
+            (a * 255.0).round() as u8,
+        ])
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use image::{Rgba, RgbaImage};
+
+    use super::{fit_size, resample};
+
+    #[test]
+    fn fit_size_keeps_aspect_ratio() {
+        assert_eq!(fit_size(64, 800, 800), (64, 64));
+        assert_eq!(fit_size(100, 800, 400), (100, 50));
+        assert_eq!(fit_size(100, 400, 800), (50, 100));
+    }
+
+    #[test]
+    fn resample_does_not_darken_edges() {
+        let image = RgbaImage::from_fn(16, 16, |x, _| {
+            if x < 8 {
+                Rgba([255, 255, 255, 255])
+            } else {
+                Rgba([0, 0, 0, 0])
+            }
+        });
+        let resized = resample(&image, 4, 4);
+        for pixel in resized.pixels() {
+            if pixel[3] > 0 {
+                assert!(pixel[0] >= 250, "{:?}", pixel);
+            }
+        }
+    }
+
+    #[test]
+    fn resample_keeps_flat_colors() {
+        let image = RgbaImage::from_pixel(10, 6, Rgba([40, 120, 200, 128]));
+        let resized = resample(&image, 5, 3);
+        assert_eq!(resized.dimensions(), (5, 3));
+        for pixel in resized.pixels() {
+            for (channel, expected) in pixel.0.iter().zip([40, 120, 200, 128]) {
+                assert!((*channel as i32 - expected).abs() <= 1, "{:?}", pixel);
+            }
+        }
+    }
+}
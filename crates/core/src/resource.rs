@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+/// Provides the external files referenced by a glTF document loaded from memory, e.g. the `.bin`
+/// buffers and the textures next to a `.gltf` file.
+///
+/// Implemented for maps of URI to content and for closures.
+pub trait ResourceResolver {
+    /// Returns the content of `uri`, as written in the glTF document, or `None` if it is unknown.
+    fn resolve(&self, uri: &str) -> Option<Vec<u8>>;
+}
+
+impl ResourceResolver for HashMap<String, Vec<u8>> {
+    fn resolve(&self, uri: &str) -> Option<Vec<u8>> {
+        self.get(uri).cloned()
+    }
+}
+
+impl<F> ResourceResolver for F
+where
+    F: Fn(&str) -> Option<Vec<u8>>,
+{
+    fn resolve(&self, uri: &str) -> Option<Vec<u8>> {
+        self(uri)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::ResourceResolver;
+
+    #[test]
+    fn map_and_closure_resolvers() {
+        let mut resources = HashMap::new();
+        resources.insert("scene.bin".to_owned(), vec![1, 2, 3]);
+        assert_eq!(resources.resolve("scene.bin"), Some(vec![1, 2, 3]));
+        assert_eq!(resources.resolve("texture.png"), None);
+
+        let resolver = |uri: &str| (uri == "texture.png").then(|| vec![4]);
+        let resolver: &dyn ResourceResolver = &resolver;
+        assert_eq!(resolver.resolve("texture.png"), Some(vec![4]));
+    }
+}
@@ -0,0 +1,32 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Picks one of the scenes of a glTF asset.
+///
+/// In configuration files an index is written as a number and a name as a string.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SceneSelector {
+    Index(usize),
+    Name(String),
+}
+
+impl fmt::Display for SceneSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Index(index) => write!(f, "#{}", index),
+            Self::Name(name) => write!(f, "\"{}\"", name),
+        }
+    }
+}
+
+impl SceneSelector {
+    /// Returns the index of the selected scene among the scenes named `scenes`.
+    pub(crate) fn resolve(&self, scenes: &[String]) -> Option<usize> {
+        match self {
+            Self::Index(index) => Some(*index).filter(|index| *index < scenes.len()),
+            Self::Name(name) => scenes.iter().position(|scene| scene == name),
+        }
+    }
+}
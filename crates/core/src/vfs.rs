@@ -0,0 +1,487 @@
+use std::{
+    collections::{hash_map::RandomState, HashMap, HashSet},
+    env, fs,
+    hash::{BuildHasher, Hasher},
+    io,
+    path::{Component, Path, PathBuf},
+    process,
+};
+
+use crate::{Result, SpaceThumbnailsError};
+
+/// A file system the sibling files of a model loaded from memory are read from, e.g. the `.mtl`
+/// and the textures of an `.obj` file. Paths are relative to the root of the file system and use
+/// `/` as separator.
+pub trait Vfs {
+    fn open(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+    /// Every file of the file system, or `None` when it can not be listed. Without a listing only
+    /// the files referenced by `.obj` and `.mtl` files can be found, the external textures of
+    /// other formats like FBX or COLLADA are missing and those models render untextured.
+    fn list(&self) -> Option<Vec<PathBuf>> {
+        None
+    }
+
+    /// Size of a file without reading it, `None` when it is not known in advance.
+    fn size(&self, _path: &Path) -> Option<u64> {
+        None
+    }
+}
+
+impl Vfs for HashMap<PathBuf, Vec<u8>> {
+    fn open(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.get(path)
+            .or_else(|| self.get(&normalize_path(path)?))
+            .cloned()
+            .ok_or_else(|| io::ErrorKind::NotFound.into())
+    }
+
+    fn list(&self) -> Option<Vec<PathBuf>> {
+        Some(self.keys().cloned().collect())
+    }
+
+    fn size(&self, path: &Path) -> Option<u64> {
+        self.get(path).map(|data| data.len() as u64)
+    }
+}
+
+/// The files under a directory of the disk. It is not listed, the model may sit in a directory
+/// full of unrelated files, so only the siblings of `.obj` files are found.
+pub(crate) struct DirectoryVfs(pub PathBuf);
+
+impl DirectoryVfs {
+    fn path(&self, path: &Path) -> io::Result<PathBuf> {
+        normalize_path(path)
+            .map(|path| self.0.join(path))
+            .ok_or_else(|| io::ErrorKind::NotFound.into())
+    }
+}
+
+impl Vfs for DirectoryVfs {
+    fn open(&self, path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(self.path(path)?)
+    }
+
+    fn size(&self, path: &Path) -> Option<u64> {
+        Some(fs::metadata(self.path(path).ok()?).ok()?.len())
+    }
+}
+
+/// Caps on what `materialize` copies to the disk, a ZIP bomb would fill it otherwise.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Limits {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+pub(crate) const LIMITS: Limits = Limits {
+    files: 4096,
+    bytes: 1 << 30,
+};
+
+/// Resolves `path`, relative to the directory of `base`, into a path from the root of the file
+/// system. `None` if it leaves the root.
+pub(crate) fn resolve_path(base: &Path, path: &str) -> Option<PathBuf> {
+    let path = path.trim().replace('\\', "/");
+    let directory = base.parent().unwrap_or_else(|| Path::new(""));
+    normalize_path(&directory.join(path.trim_start_matches('/')))
+}
+
+/// Removes `.` and `..` components, `None` if the path leaves the root.
+pub(crate) fn normalize_path(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => normalized.push(name),
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+        }
+    }
+    Some(normalized)
+}
+
+/// Decodes the percent-encoded characters of a glTF URI.
+pub(crate) fn decode_uri(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = bytes
+            .get(index + 1..index + 3)
+            .filter(|_| bytes[index] == b'%')
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Files referenced by an `.obj` (material libraries) or `.mtl` (texture maps) file.
+pub(crate) fn referenced_files(data: &[u8]) -> Vec<String> {
+    let mut files = Vec::new();
+    for line in String::from_utf8_lossy(data).lines() {
+        let (keyword, arguments) = match line.trim().split_once(char::is_whitespace) {
+            Some(statement) => statement,
+            None => continue,
+        };
+        if keyword == "mtllib" {
+            // a line may list several libraries
+            files.extend(arguments.split_whitespace().map(|name| name.to_owned()));
+        } else if keyword.starts_with("map_")
+            || matches!(keyword, "bump" | "disp" | "decal" | "refl" | "norm")
+        {
+            files.extend(texture_file_name(arguments).map(|name| name.to_owned()));
+        }
+    }
+    files
+}
+
+/// The file name of a texture map statement, the rest of the line after the options like
+/// `-bm 1.0`. Unlike libraries, texture file names may contain spaces.
+fn texture_file_name(arguments: &str) -> Option<&str> {
+    let next_token = |text: &str| -> (usize, usize) {
+        let end = text.find(char::is_whitespace).unwrap_or(text.len());
+        let next = text[end..].len() - text[end..].trim_start().len();
+        (end, end + next)
+    };
+    let mut rest = arguments.trim();
+    loop {
+        let (end, next) = next_token(rest);
+        let values = match &rest[..end] {
+            "-blendu" | "-blendv" | "-bm" | "-boost" | "-cc" | "-clamp" | "-imfchan"
+            | "-texres" | "-type" => 1,
+            "-mm" => 2,
+            "-o" | "-s" | "-t" => 3,
+            _ => break,
+        };
+        rest = &rest[next..];
+        for index in 0..values {
+            let (end, next) = next_token(rest);
+            // `-o`, `-s` and `-t` take one to three numbers
+            if index > 0 && values == 3 && rest[..end].parse::<f32>().is_err() {
+                break;
+            }
+            rest = &rest[next..];
+        }
+    }
+    (!rest.is_empty()).then_some(rest)
+}
+
+/// A temporary directory removed when dropped. Its name is random and it is always newly created,
+/// so nothing planted in the shared temporary directory is read or written through.
+pub(crate) struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    pub fn new() -> Result<Self> {
+        let mut attempts = 0;
+        loop {
+            // every `RandomState` has fresh random keys
+            let suffix = RandomState::new().build_hasher().finish();
+            let path = env::temp_dir().join(format!(
+                "space-thumbnails-{}-{:016x}",
+                process::id(),
+                suffix
+            ));
+            match create_private_dir(&path) {
+                Ok(()) => return Ok(Self { path }),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists && attempts < 16 => {
+                    attempts += 1;
+                }
+                Err(source) => return Err(SpaceThumbnailsError::Io { path, source }),
+            }
+        }
+    }
+
+    /// Writes `data` at `path` relative to the directory.
+    pub fn write(&self, path: &Path, data: &[u8]) -> Result<PathBuf> {
+        let target = self.path.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|source| SpaceThumbnailsError::Io {
+                path: parent.to_owned(),
+                source,
+            })?;
+        }
+        fs::write(&target, data).map_err(|source| SpaceThumbnailsError::Io {
+            path: target.clone(),
+            source,
+        })?;
+        Ok(target)
+    }
+}
+
+/// Creates a directory only the current user can access, failing if it exists.
+fn create_private_dir(path: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(path)
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Copies the model `buffer`, stored at `filename` in `vfs`, and its sibling files into a
+/// temporary directory so that loaders reading from the disk find them. Returns the directory and
+/// the path of the model in it.
+///
+/// The files referenced by `.obj` and `.mtl` files are copied wherever they are, and when `vfs`
+/// can be listed so are the files under the directory of the model.
+pub(crate) fn materialize(
+    vfs: &dyn Vfs,
+    buffer: &[u8],
+    filename: &Path,
+    limits: Limits,
+) -> Result<(TempDir, PathBuf)> {
+    let filename = normalize_path(filename)
+        .filter(|filename| filename.file_name().is_some())
+        .ok_or_else(|| SpaceThumbnailsError::InvalidFilename(filename.to_owned()))?;
+    let mut copier = Copier {
+        vfs,
+        directory: TempDir::new()?,
+        copied: HashSet::from([filename.clone()]),
+        bytes: buffer.len() as u64,
+        limits,
+    };
+    copier.check(0)?;
+
+    let mut pending = vec![(filename.clone(), buffer.to_vec())];
+    while let Some((path, data)) = pending.pop() {
+        for reference in referenced_files(&data) {
+            let file = match resolve_path(&path, &reference) {
+                Some(file) => file,
+                None => continue,
+            };
+            if let Some(data) = copier.copy(&file)? {
+                if matches!(file.extension(), Some(e) if e.eq_ignore_ascii_case("mtl")) {
+                    pending.push((file, data));
+                }
+            }
+        }
+    }
+
+    if let Some(files) = vfs.list() {
+        let root = filename.parent().unwrap_or_else(|| Path::new(""));
+        for file in files.iter().filter_map(|file| normalize_path(file)) {
+            if file.starts_with(root) {
+                copier.copy(&file)?;
+            }
+        }
+    }
+
+    let model = copier.directory.write(&filename, buffer)?;
+    Ok((copier.directory, model))
+}
+
+struct Copier<'a> {
+    vfs: &'a dyn Vfs,
+    directory: TempDir,
+    copied: HashSet<PathBuf>,
+    bytes: u64,
+    limits: Limits,
+}
+
+impl Copier<'_> {
+    /// Copies `file` unless it already was, returns its content. `None` when it was copied before
+    /// or can not be read.
+    fn copy(&mut self, file: &Path) -> Result<Option<Vec<u8>>> {
+        if self.copied.contains(file) {
+            return Ok(None);
+        }
+        self.copied.insert(file.to_owned());
+        // the announced size is checked before the file is decompressed
+        self.check(self.vfs.size(file).unwrap_or(0))?;
+        let data = match self.vfs.open(file) {
+            Ok(data) => data,
+            Err(_) => return Ok(None),
+        };
+        self.bytes += data.len() as u64;
+        self.check(0)?;
+        self.directory.write(file, &data)?;
+        Ok(Some(data))
+    }
+
+    fn check(&self, additional_bytes: u64) -> Result<()> {
+        if self.copied.len() > self.limits.files
+            || self.bytes.saturating_add(additional_bytes) > self.limits.bytes
+        {
+            return Err(SpaceThumbnailsError::ResourceLimit {
+                files: self.limits.files,
+                bytes: self.limits.bytes,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashMap,
+        fs,
+        path::{Path, PathBuf},
+    };
+
+    use crate::SpaceThumbnailsError;
+
+    use super::{
+        decode_uri, materialize, referenced_files, resolve_path, DirectoryVfs, Limits, TempDir,
+        Vfs, LIMITS,
+    };
+
+    #[test]
+    fn relative_paths() {
+        let base = Path::new("models/chair.obj");
+        assert_eq!(
+            resolve_path(base, "chair.mtl"),
+            Some(PathBuf::from("models/chair.mtl"))
+        );
+        assert_eq!(
+            resolve_path(base, "..\\textures\\wood.png"),
+            Some(PathBuf::from("textures/wood.png"))
+        );
+        assert_eq!(resolve_path(base, "../../secret"), None);
+        assert_eq!(decode_uri("my%20texture%.png"), "my texture%.png");
+    }
+
+    #[test]
+    fn obj_references() {
+        let obj = b"mtllib chair.mtl\nv 0 0 0\nusemtl wood\n";
+        assert_eq!(referenced_files(obj), vec!["chair.mtl"]);
+        let mtl = b"newmtl wood\nmap_Kd textures/wood.png\nmap_Bump -bm 0.5 bump.png\n";
+        assert_eq!(referenced_files(mtl), vec!["textures/wood.png", "bump.png"]);
+
+        assert_eq!(
+            referenced_files(b"mtllib chair.mtl  table.mtl\n"),
+            vec!["chair.mtl", "table.mtl"]
+        );
+        let mtl =
+            b"map_Kd -o 0.5 0.5 -s 2 -clamp on dark wood.png\nmap_Ka -mm 0 1 -t 1 2 3 ao.png\n";
+        assert_eq!(referenced_files(mtl), vec!["dark wood.png", "ao.png"]);
+        assert!(referenced_files(b"map_Kd -bm 0.5\n").is_empty());
+    }
+
+    /// A file system that can not be listed.
+    struct Unlisted(HashMap<PathBuf, Vec<u8>>);
+
+    impl Vfs for Unlisted {
+        fn open(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+            self.0.open(path)
+        }
+    }
+
+    #[test]
+    fn materialize_obj_siblings() {
+        let obj = b"mtllib chair.mtl\n".to_vec();
+        let mut files = HashMap::new();
+        files.insert(PathBuf::from("models/chair.obj"), obj.clone());
+        files.insert(
+            PathBuf::from("models/chair.mtl"),
+            b"map_Kd ../textures/wood.png\n".to_vec(),
+        );
+        files.insert(PathBuf::from("textures/wood.png"), vec![1, 2, 3]);
+        files.insert(PathBuf::from("unrelated.bin"), vec![0]);
+        let vfs = Unlisted(files);
+
+        let (directory, model) =
+            materialize(&vfs, &obj, Path::new("models/chair.obj"), LIMITS).unwrap();
+        let root = model.parent().unwrap().parent().unwrap().to_owned();
+        assert!(model.ends_with("models/chair.obj"));
+        assert_eq!(
+            fs::read(root.join("textures/wood.png")).unwrap(),
+            vec![1, 2, 3]
+        );
+        assert!(!root.join("unrelated.bin").exists());
+
+        drop(directory);
+        assert!(!root.exists());
+    }
+
+    #[test]
+    fn materialize_model_directory() {
+        let mut files = HashMap::new();
+        files.insert(PathBuf::from("models/chair.fbx"), vec![0]);
+        files.insert(PathBuf::from("models/textures/wood.png"), vec![1, 2, 3]);
+        files.insert(PathBuf::from("other/table.fbx"), vec![4]);
+
+        let (_directory, model) =
+            materialize(&files, &[0], Path::new("models/chair.fbx"), LIMITS).unwrap();
+        let root = model.parent().unwrap().parent().unwrap().to_owned();
+        assert!(root.join("models/textures/wood.png").exists());
+        assert!(!root.join("other/table.fbx").exists());
+    }
+
+    #[test]
+    fn temp_dirs_are_new() {
+        let first = TempDir::new().unwrap();
+        let second = TempDir::new().unwrap();
+        assert_ne!(first.path, second.path);
+        let path = first.path.clone();
+        assert!(fs::read_dir(&path).unwrap().next().is_none());
+        drop(first);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn materialize_from_directory() {
+        let source = TempDir::new().unwrap();
+        let obj = b"mtllib chair.mtl\nv 0 0 0\n";
+        let model = source.write(Path::new("chair.bin"), obj).unwrap();
+        source
+            .write(Path::new("chair.mtl"), b"newmtl wood\n")
+            .unwrap();
+        source.write(Path::new("other.png"), &[0]).unwrap();
+
+        let directory = DirectoryVfs(model.parent().unwrap().to_owned());
+        let (_directory, model) =
+            materialize(&directory, obj, Path::new("chair.obj"), LIMITS).unwrap();
+        let root = model.parent().unwrap();
+        assert!(root.join("chair.mtl").exists());
+        assert!(!root.join("other.png").exists());
+    }
+
+    #[test]
+    fn materialize_limits() {
+        let mut files = HashMap::new();
+        for index in 0..4 {
+            files.insert(PathBuf::from(format!("texture{}.png", index)), vec![0; 100]);
+        }
+        let load = |limits| materialize(&files, &[0], Path::new("model.fbx"), limits);
+
+        assert!(load(Limits {
+            files: 5,
+            bytes: 401
+        })
+        .is_ok());
+        assert!(matches!(
+            load(Limits {
+                files: 3,
+                bytes: 1000
+            }),
+            Err(SpaceThumbnailsError::ResourceLimit { files: 3, .. })
+        ));
+        assert!(matches!(
+            load(Limits {
+                files: 5,
+                bytes: 250
+            }),
+            Err(SpaceThumbnailsError::ResourceLimit { bytes: 250, .. })
+        ));
+    }
+}
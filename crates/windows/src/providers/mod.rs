@@ -4,8 +4,18 @@ mod thumbnail_file;
 pub use thumbnail::*;
 pub use thumbnail_file::*;
 
+use space_thumbnails::{RenderOptions, RendererBackend};
+
 use crate::registry::RegistryKey;
 
+/// Options of every thumbnail rendered by the shell extension.
+pub fn thumbnail_options(backend: RendererBackend) -> RenderOptions {
+    RenderOptions::builder()
+        .backend(backend)
+        .size(256, 256)
+        .build()
+}
+
 pub trait Provider {
     fn clsid(&self) -> windows::core::GUID;
     fn register(&self, module_path: &str) -> Vec<RegistryKey>;
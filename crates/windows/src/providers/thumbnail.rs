@@ -5,7 +5,7 @@ use std::{
 };
 
 use log::{info, warn};
-use space_thumbnails::{RendererBackend, SpaceThumbnailsRenderer};
+use space_thumbnails::{RendererBackend, SpaceThumbnailsError, SpaceThumbnailsRenderer};
 use windows::{
     core::{implement, IUnknown, Interface, GUID},
     Win32::{
@@ -19,10 +19,10 @@ use windows::{
 use crate::{
     constant::{ERROR_256X256_ARGB, TIMEOUT_256X256_ARGB, TOOLARGE_256X256_ARGB},
     registry::{register_clsid, RegistryData, RegistryKey, RegistryValue},
-    utils::{create_argb_bitmap, run_timeout, WinStream},
+    utils::{create_argb_bitmap, error_chain, run_timeout, write_status_bitmap, WinStream},
 };
 
-use super::Provider;
+use super::{thumbnail_options, Provider};
 
 pub struct ThumbnailProvider {
     pub clsid: GUID,
@@ -99,7 +99,8 @@ impl IThumbnailProvider_Impl for ThumbnailHandler {
         phbmp: *mut HBITMAP,
         pdwalpha: *mut WTS_ALPHATYPE,
     ) -> windows::core::Result<()> {
-        let size = 256;
+        let options = thumbnail_options(RendererBackend::Vulkan);
+        let size = options.width;
         let mut stream = self
             .stream
             .take()
@@ -109,17 +110,7 @@ impl IThumbnailProvider_Impl for ThumbnailHandler {
         if filesize > 300 * 1024 * 1024
         /* 300 MB */
         {
-            unsafe {
-                let mut p_bits: *mut core::ffi::c_void = core::ptr::null_mut();
-                let hbmp = create_argb_bitmap(256, 256, &mut p_bits);
-                std::ptr::copy(
-                    TOOLARGE_256X256_ARGB.as_ptr(),
-                    p_bits as *mut _,
-                    TOOLARGE_256X256_ARGB.len(),
-                );
-                phbmp.write(hbmp);
-                pdwalpha.write(WTSAT_ARGB);
-            }
+            unsafe { write_status_bitmap(TOOLARGE_256X256_ARGB, phbmp, pdwalpha) };
             return Ok(());
         }
 
@@ -135,20 +126,17 @@ impl IThumbnailProvider_Impl for ThumbnailHandler {
 
         let timeout_result = run_timeout(
             move || {
-                let mut renderer = SpaceThumbnailsRenderer::new(RendererBackend::Vulkan, size, size);
-                renderer.load_asset_from_memory(
-                    buffer.as_slice(),
-                    format!("inmemory{}", filename_hint),
-                )?;
+                let mut renderer = SpaceThumbnailsRenderer::with_options(&options)?;
+                renderer.load_asset_from_bytes(buffer.as_slice(), Some(filename_hint))?;
                 let mut screenshot_buffer = vec![0; renderer.get_screenshot_size_in_byte()];
-                renderer.take_screenshot_sync(screenshot_buffer.as_mut_slice());
-                Some(screenshot_buffer)
+                renderer.take_screenshot_sync(screenshot_buffer.as_mut_slice())?;
+                Ok::<_, SpaceThumbnailsError>(screenshot_buffer)
             },
             Duration::from_secs(5),
         );
 
         match timeout_result {
-            Ok(Some(screenshot_buffer)) => {
+            Ok(Ok(screenshot_buffer)) => {
                 info!(target: "ThumbnailProvider", "Rendering thumbnails success [{}], Elapsed: {:.2?}", self.filename_hint, start_time.elapsed());
                 unsafe {
                     let mut p_bits: *mut core::ffi::c_void = core::ptr::null_mut();
@@ -172,32 +160,17 @@ impl IThumbnailProvider_Impl for ThumbnailHandler {
             }
             Err(err) if err.kind() == io::ErrorKind::TimedOut => {
                 warn!(target: "ThumbnailProvider", "Rendering thumbnails timeout [{}], Elapsed: {:.2?}", self.filename_hint, start_time.elapsed());
-                unsafe {
-                    let mut p_bits: *mut core::ffi::c_void = core::ptr::null_mut();
-                    let hbmp = create_argb_bitmap(256, 256, &mut p_bits);
-                    std::ptr::copy(
-                        TIMEOUT_256X256_ARGB.as_ptr(),
-                        p_bits as *mut _,
-                        TIMEOUT_256X256_ARGB.len(),
-                    );
-                    phbmp.write(hbmp);
-                    pdwalpha.write(WTSAT_ARGB);
-                }
+                unsafe { write_status_bitmap(TIMEOUT_256X256_ARGB, phbmp, pdwalpha) };
                 Ok(())
             }
-            Err(_) | Ok(None) => {
-                warn!(target: "ThumbnailProvider", "Rendering thumbnails error [{}], Elapsed: {:.2?}", self.filename_hint, start_time.elapsed());
-                unsafe {
-                    let mut p_bits: *mut core::ffi::c_void = core::ptr::null_mut();
-                    let hbmp = create_argb_bitmap(256, 256, &mut p_bits);
-                    std::ptr::copy(
-                        ERROR_256X256_ARGB.as_ptr(),
-                        p_bits as *mut _,
-                        ERROR_256X256_ARGB.len(),
-                    );
-                    phbmp.write(hbmp);
-                    pdwalpha.write(WTSAT_ARGB);
-                }
+            Ok(Err(err)) => {
+                warn!(target: "ThumbnailProvider", "Rendering thumbnails error [{}]: {}, Elapsed: {:.2?}", self.filename_hint, error_chain(&err), start_time.elapsed());
+                unsafe { write_status_bitmap(ERROR_256X256_ARGB, phbmp, pdwalpha) };
+                Ok(())
+            }
+            Err(err) => {
+                warn!(target: "ThumbnailProvider", "Rendering thumbnails error [{}]: {}, Elapsed: {:.2?}", self.filename_hint, err, start_time.elapsed());
+                unsafe { write_status_bitmap(ERROR_256X256_ARGB, phbmp, pdwalpha) };
                 Ok(())
             }
         }
@@ -7,7 +7,7 @@ use std::{
 };
 
 use log::info;
-use space_thumbnails::{RendererBackend, SpaceThumbnailsRenderer};
+use space_thumbnails::{RendererBackend, SpaceThumbnailsError, SpaceThumbnailsRenderer};
 use windows::{
     core::{implement, IUnknown, Interface, GUID},
     Win32::{
@@ -23,10 +23,10 @@ use windows::{
 use crate::{
     constant::{ERROR_256X256_ARGB, TIMEOUT_256X256_ARGB, TOOLARGE_256X256_ARGB},
     registry::{register_clsid, RegistryData, RegistryKey, RegistryValue},
-    utils::{create_argb_bitmap, run_timeout},
+    utils::{create_argb_bitmap, error_chain, run_timeout, write_status_bitmap},
 };
 
-use super::Provider;
+use super::{thumbnail_options, Provider};
 
 pub struct ThumbnailFileProvider {
     pub clsid: GUID,
@@ -106,7 +106,8 @@ impl IThumbnailProvider_Impl for ThumbnailFileHandler {
         pdwalpha: *mut WTS_ALPHATYPE,
     ) -> windows::core::Result<()> {
         let filepath = self.filepath.take();
-        let size = 256;
+        let options = thumbnail_options(self.backend);
+        let size = options.width;
 
         if filepath.is_empty() {
             return Err(windows::core::Error::from(E_FAIL));
@@ -114,17 +115,7 @@ impl IThumbnailProvider_Impl for ThumbnailFileHandler {
 
         if matches!(fs::metadata(&filepath), Ok(metadata) if metadata.len() > 300 * 1024 * 1024 /* 300 MB */)
         {
-            unsafe {
-                let mut p_bits: *mut core::ffi::c_void = core::ptr::null_mut();
-                let hbmp = create_argb_bitmap(256, 256, &mut p_bits);
-                std::ptr::copy(
-                    TOOLARGE_256X256_ARGB.as_ptr(),
-                    p_bits as *mut _,
-                    TOOLARGE_256X256_ARGB.len(),
-                );
-                phbmp.write(hbmp);
-                pdwalpha.write(WTSAT_ARGB);
-            }
+            unsafe { write_status_bitmap(TOOLARGE_256X256_ARGB, phbmp, pdwalpha) };
             return Ok(());
         }
 
@@ -132,20 +123,19 @@ impl IThumbnailProvider_Impl for ThumbnailFileHandler {
         info!(target: "ThumbnailFileProvider", "Getting thumbnail from file: {}", filepath);
 
         let filepath_clone = filepath.clone();
-        let backend = self.backend;
         let timeout_result = run_timeout(
             move || {
-                let mut renderer = SpaceThumbnailsRenderer::new(backend, size, size);
+                let mut renderer = SpaceThumbnailsRenderer::with_options(&options)?;
                 renderer.load_asset_from_file(filepath_clone)?;
                 let mut screenshot_buffer = vec![0; renderer.get_screenshot_size_in_byte()];
-                renderer.take_screenshot_sync(screenshot_buffer.as_mut_slice());
-                Some(screenshot_buffer)
+                renderer.take_screenshot_sync(screenshot_buffer.as_mut_slice())?;
+                Ok::<_, SpaceThumbnailsError>(screenshot_buffer)
             },
             Duration::from_secs(5),
         );
 
         match timeout_result {
-            Ok(Some(screenshot_buffer)) => {
+            Ok(Ok(screenshot_buffer)) => {
                 info!(target: "ThumbnailFileProvider", "Rendering thumbnails success file: {}, Elapsed: {:.2?}", filepath, start_time.elapsed());
                 unsafe {
                     let mut p_bits: *mut core::ffi::c_void = core::ptr::null_mut();
@@ -169,32 +159,17 @@ impl IThumbnailProvider_Impl for ThumbnailFileHandler {
             }
             Err(err) if err.kind() == io::ErrorKind::TimedOut => {
                 info!(target: "ThumbnailFileProvider", "Rendering thumbnails timeout file: {}, Elapsed: {:.2?}", filepath, start_time.elapsed());
-                unsafe {
-                    let mut p_bits: *mut core::ffi::c_void = core::ptr::null_mut();
-                    let hbmp = create_argb_bitmap(256, 256, &mut p_bits);
-                    std::ptr::copy(
-                        TIMEOUT_256X256_ARGB.as_ptr(),
-                        p_bits as *mut _,
-                        TIMEOUT_256X256_ARGB.len(),
-                    );
-                    phbmp.write(hbmp);
-                    pdwalpha.write(WTSAT_ARGB);
-                }
+                unsafe { write_status_bitmap(TIMEOUT_256X256_ARGB, phbmp, pdwalpha) };
                 Ok(())
             }
-            Err(_) | Ok(None) => {
-                info!(target: "ThumbnailFileProvider", "Rendering thumbnails error file: {}, Elapsed: {:.2?}", filepath, start_time.elapsed());
-                unsafe {
-                    let mut p_bits: *mut core::ffi::c_void = core::ptr::null_mut();
-                    let hbmp = create_argb_bitmap(256, 256, &mut p_bits);
-                    std::ptr::copy(
-                        ERROR_256X256_ARGB.as_ptr(),
-                        p_bits as *mut _,
-                        ERROR_256X256_ARGB.len(),
-                    );
-                    phbmp.write(hbmp);
-                    pdwalpha.write(WTSAT_ARGB);
-                }
+            Ok(Err(err)) => {
+                info!(target: "ThumbnailFileProvider", "Rendering thumbnails error file: {}: {}, Elapsed: {:.2?}", filepath, error_chain(&err), start_time.elapsed());
+                unsafe { write_status_bitmap(ERROR_256X256_ARGB, phbmp, pdwalpha) };
+                Ok(())
+            }
+            Err(err) => {
+                info!(target: "ThumbnailFileProvider", "Rendering thumbnails error file: {}: {}, Elapsed: {:.2?}", filepath, err, start_time.elapsed());
+                unsafe { write_status_bitmap(ERROR_256X256_ARGB, phbmp, pdwalpha) };
                 Ok(())
             }
         }
@@ -1,4 +1,5 @@
 use std::{
+    error::Error,
     io, mem,
     sync::{atomic::AtomicBool, Arc},
     thread,
@@ -8,6 +9,7 @@ use std::{
 use windows::Win32::{
     Graphics::Gdi::{CreateDIBSection, BITMAPINFO, BITMAPINFOHEADER, DIB_RGB_COLORS, HBITMAP, HDC},
     System::Com::{IStream, STATSTG},
+    UI::Shell::{WTSAT_ARGB, WTS_ALPHATYPE},
 };
 
 pub fn run_timeout<T: Send + 'static>(
@@ -41,6 +43,18 @@ pub fn run_timeout<T: Send + 'static>(
     }
 }
 
+/// Formats an error together with all of its sources, e.g. `failed to parse fbx asset: ...`.
+pub fn error_chain(err: &dyn Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
 pub struct WinStream {
     stream: IStream,
 }
@@ -103,3 +117,16 @@ pub unsafe fn create_argb_bitmap(
         0,
     )
 }
+
+/// Returns one of the 256x256 ARGB status images, e.g. `ERROR_256X256_ARGB`, as the thumbnail.
+pub unsafe fn write_status_bitmap(
+    image: &[u8],
+    phbmp: *mut HBITMAP,
+    pdwalpha: *mut WTS_ALPHATYPE,
+) {
+    let mut p_bits: *mut core::ffi::c_void = core::ptr::null_mut();
+    let hbmp = create_argb_bitmap(256, 256, &mut p_bits);
+    std::ptr::copy(image.as_ptr(), p_bits as *mut _, image.len());
+    phbmp.write(hbmp);
+    pdwalpha.write(WTSAT_ARGB);
+}